@@ -0,0 +1,122 @@
+use crate::{
+    models::{funds::FundsAmount, share_amount::ShareAmount},
+    state::dao_app_state::{CentralAppGlobalState, CentralAppInvestorState},
+};
+use anyhow::{anyhow, Result};
+use rust_decimal::prelude::ToPrimitive;
+
+/// Total income (`received`) the DAO needs, such that the investor's dividend equals their investment:
+/// `original investment / (investors share * investor's ownership fraction)`
+///
+/// This is a decision-support metric: it's independent of what the DAO has received (or the investor claimed) so far.
+/// `total_shares` is the amount of shares the ownership fraction is calculated with.
+pub fn break_even_received(
+    investor: &CentralAppInvestorState,
+    global: &CentralAppGlobalState,
+    total_shares: ShareAmount,
+) -> Result<FundsAmount> {
+    if total_shares.val() == 0 {
+        return Err(anyhow!("Total shares must not be 0"));
+    }
+    if investor.shares > total_shares {
+        return Err(anyhow!(
+            "Investor shares: {} must be <= total shares: {total_shares}",
+            investor.shares
+        ));
+    }
+
+    let investment = investor.original_investment(global)?.as_decimal();
+
+    let ownership = investor.shares.as_decimal() / total_shares.as_decimal();
+    let denominator = global.investors_share.value() * ownership;
+    if denominator.is_zero() {
+        return Err(anyhow!(
+            "Can't calculate break-even: investor doesn't participate in income (investors share: {:?}, shares: {})",
+            global.investors_share,
+            investor.shares
+        ));
+    }
+
+    // round up: the investment is returned only when received reaches this
+    let received = investment
+        .checked_div(denominator)
+        .ok_or_else(|| anyhow!("Failed: {investment} / {denominator}"))?
+        .ceil();
+
+    Ok(FundsAmount::new(received.to_u64().ok_or_else(|| {
+        anyhow!("Break-even received: {received} doesn't fit in u64")
+    })?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::break_even_received;
+    use crate::{
+        fixtures::{global_state, investor_state, percentage},
+        models::{funds::FundsAmount, share_amount::ShareAmount},
+    };
+    use anyhow::Result;
+
+    #[test]
+    fn test_break_even_for_dao_owned_100_percent() -> Result<()> {
+        let mut global = global_state();
+        global.share_price = FundsAmount::new(10);
+        global.investors_share = percentage("0.4");
+        let investor = investor_state(100);
+
+        // investment: 100 shares * 10 = 1000, all the investor's part of the income goes to the investor
+        let res = break_even_received(&investor, &global, ShareAmount::new(100))?;
+        assert_eq!(FundsAmount::new(2500), res);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_break_even_for_partial_ownership() -> Result<()> {
+        let mut global = global_state();
+        global.share_price = FundsAmount::new(10);
+        global.investors_share = percentage("0.5");
+        let investor = investor_state(10);
+
+        // investment: 10 shares * 10 = 100, owns 10%, of 50% of the income
+        let res = break_even_received(&investor, &global, ShareAmount::new(100))?;
+        assert_eq!(FundsAmount::new(2000), res);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_break_even_rounds_up() -> Result<()> {
+        let mut global = global_state();
+        global.share_price = FundsAmount::new(1);
+        global.investors_share = percentage("0.3");
+        let investor = investor_state(1);
+
+        // 1 / 0.3 = 3.33..
+        let res = break_even_received(&investor, &global, ShareAmount::new(1))?;
+        assert_eq!(FundsAmount::new(4), res);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_break_even_errors_with_zero_denominators() -> Result<()> {
+        let global = global_state();
+        assert!(break_even_received(&investor_state(10), &global, ShareAmount::new(0)).is_err());
+        assert!(break_even_received(&investor_state(0), &global, ShareAmount::new(100)).is_err());
+
+        let mut global = global_state();
+        global.investors_share = percentage("0");
+        assert!(break_even_received(&investor_state(10), &global, ShareAmount::new(100)).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_break_even_errors_if_investor_has_more_than_total_shares() -> Result<()> {
+        let res = break_even_received(&investor_state(101), &global_state(), ShareAmount::new(100));
+        assert!(res.is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,101 @@
+use crate::{
+    models::share_amount::ShareAmount,
+    state::dao_app_state::{CentralAppGlobalState, CentralAppInvestorState, ProspectusSignature},
+};
+use algonaut::core::Address;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComplianceSummary {
+    pub has_prospectus: bool,
+    /// Investors (with locked shares) that didn't sign the prospectus
+    pub unsigned_investors: usize,
+    /// Investors (with locked shares) that signed a prospectus that's not the current one
+    pub stale_signature_investors: usize,
+    /// Fraction of `total_shares` held by unsigned or stale signature investors
+    pub unsigned_shares_fraction: f64,
+}
+
+impl ComplianceSummary {
+    pub fn all_investors_signed(&self) -> bool {
+        self.unsigned_investors == 0 && self.stale_signature_investors == 0
+    }
+}
+
+/// An overview of the DAO's compliance-relevant state, for the DAO and its investors.
+/// Whitelisting and KYC settings aren't stored in the DAO's state, so they're not part of this summary.
+pub fn compliance_summary(
+    global: &CentralAppGlobalState,
+    states: &[(Address, CentralAppInvestorState)],
+    total_shares: ShareAmount,
+) -> ComplianceSummary {
+    let mut unsigned_investors = 0;
+    let mut stale_signature_investors = 0;
+    let mut unsigned_shares = 0;
+
+    for (_, state) in states.iter().filter(|(_, state)| state.shares.val() > 0) {
+        match state.prospectus_signature(global) {
+            ProspectusSignature::Unsigned => unsigned_investors += 1,
+            ProspectusSignature::Stale => stale_signature_investors += 1,
+            ProspectusSignature::Signed | ProspectusSignature::NotRequired => continue,
+        }
+        unsigned_shares += state.shares.val();
+    }
+
+    let unsigned_shares_fraction = if total_shares.val() == 0 {
+        0.0
+    } else {
+        unsigned_shares as f64 / total_shares.val() as f64
+    };
+
+    ComplianceSummary {
+        has_prospectus: global.prospectus.is_some(),
+        unsigned_investors,
+        stale_signature_investors,
+        unsigned_shares_fraction,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::compliance_summary;
+    use crate::{
+        fixtures::{funded_and_operating, mid_raise, SHARE_SUPPLY},
+        state::dao_app_state::Prospectus,
+    };
+    use anyhow::Result;
+
+    #[test]
+    fn test_summary_with_all_investors_signed() -> Result<()> {
+        let snapshot = mid_raise();
+
+        let summary = compliance_summary(&snapshot.global, &snapshot.investors, SHARE_SUPPLY);
+
+        assert!(summary.has_prospectus);
+        assert!(summary.all_investors_signed());
+        assert_eq!(0.0, summary.unsigned_shares_fraction);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_summary_with_unsigned_and_stale_investors() -> Result<()> {
+        let mut snapshot = funded_and_operating();
+        // 6_000 shares
+        snapshot.investors[0].1.signed_prospectus = None;
+        // 3_000 shares
+        if let Some(signed) = &mut snapshot.investors[1].1.signed_prospectus {
+            signed.hash = Prospectus::new(b"old version", "".to_owned()).hash;
+        }
+
+        let summary = compliance_summary(&snapshot.global, &snapshot.investors, SHARE_SUPPLY);
+
+        assert_eq!(1, summary.unsigned_investors);
+        assert_eq!(1, summary.stale_signature_investors);
+        // 9_000 of 24_000
+        assert_eq!(0.375, summary.unsigned_shares_fraction);
+        assert!(!summary.all_investors_signed());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,106 @@
+use crate::state::dao_app_state::CentralAppInvestorState;
+use algonaut::core::Address;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConcentrationMetrics {
+    /// Investors with locked shares
+    pub holders: usize,
+    /// Herfindahl-Hirschman Index, calculated with the ownership fractions (so in [0..1] instead of [0..10000]):
+    /// 1 / holders if the shares are distributed evenly, 1 if one holder has all the shares.
+    pub hhi: f64,
+    /// Fraction of the shares held by the largest holder
+    pub top_1: f64,
+    /// Fraction of the shares held by the 5 largest holders
+    pub top_5: f64,
+    /// Fraction of the shares held by the 10 largest holders
+    pub top_10: f64,
+}
+
+/// How concentrated the locked shares are between the investors. High concentration means that a few holders dominate.
+/// All metrics are 0 if nobody has locked shares.
+pub fn investor_concentration(
+    states: &[(Address, CentralAppInvestorState)],
+) -> ConcentrationMetrics {
+    let total: u64 = states.iter().map(|(_, state)| state.shares.val()).sum();
+
+    let mut fractions: Vec<f64> = states
+        .iter()
+        .map(|(_, state)| state.shares.val())
+        .filter(|shares| *shares > 0)
+        .map(|shares| shares as f64 / total as f64)
+        .collect();
+    // descending
+    fractions.sort_by(|a, b| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+
+    let top = |n: usize| -> f64 { fractions.iter().take(n).sum() };
+
+    ConcentrationMetrics {
+        holders: fractions.len(),
+        hhi: fractions.iter().map(|f| f * f).sum(),
+        top_1: top(1),
+        top_5: top(5),
+        top_10: top(10),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::investor_concentration;
+    use crate::fixtures::investor_state;
+    use algonaut::core::Address;
+    use anyhow::Result;
+
+    fn assert_close(expected: f64, actual: f64) {
+        assert!(
+            (expected - actual).abs() < 1e-9,
+            "expected: {expected}, actual: {actual}"
+        );
+    }
+
+    #[test]
+    fn test_perfectly_distributed_shares() -> Result<()> {
+        let states: Vec<_> = (0..20)
+            .map(|i| (Address([i; 32]), investor_state(100)))
+            .collect();
+
+        let metrics = investor_concentration(&states);
+
+        assert_eq!(20, metrics.holders);
+        assert_close(0.05, metrics.hhi);
+        assert_close(0.05, metrics.top_1);
+        assert_close(0.25, metrics.top_5);
+        assert_close(0.5, metrics.top_10);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_single_holder() -> Result<()> {
+        let states = vec![
+            (Address([1; 32]), investor_state(100)),
+            // opted in, without shares
+            (Address([2; 32]), investor_state(0)),
+        ];
+
+        let metrics = investor_concentration(&states);
+
+        assert_eq!(1, metrics.holders);
+        assert_close(1.0, metrics.hhi);
+        assert_close(1.0, metrics.top_1);
+        assert_close(1.0, metrics.top_5);
+        assert_close(1.0, metrics.top_10);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_holders() -> Result<()> {
+        let metrics = investor_concentration(&[]);
+
+        assert_eq!(0, metrics.holders);
+        assert_close(0.0, metrics.hhi);
+        assert_close(0.0, metrics.top_1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,150 @@
+use crate::models::{funds::FundsAmount, timestamp::Timestamp};
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdherenceReport {
+    /// Number of complete periods covered by the history
+    pub periods: u64,
+    /// Periods in which `received` didn't grow
+    pub missed_periods: Vec<Period>,
+}
+
+impl AdherenceReport {
+    pub fn is_adherent(&self) -> bool {
+        self.missed_periods.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Period {
+    pub start: Timestamp,
+    pub end: Timestamp,
+}
+
+/// Checks whether the DAO received income in every `expected_period`, given a history of its `received` global state.
+///
+/// Periods start at the first history entry, and only complete periods (ending at or before the last entry) are evaluated.
+/// `received` at a point in time is the last history value at or before it. The history doesn't need to be sorted.
+/// A zero `expected_period` yields a report without periods.
+pub fn distribution_adherence(
+    history: &[(Timestamp, FundsAmount)],
+    expected_period: Duration,
+) -> AdherenceReport {
+    let period = expected_period.as_secs();
+
+    let mut history = history.to_vec();
+    history.sort_by_key(|(timestamp, _)| *timestamp);
+
+    let (first, last) = match (history.first(), history.last()) {
+        (Some(first), Some(last)) if period > 0 => (first.0, last.0),
+        _ => {
+            return AdherenceReport {
+                periods: 0,
+                missed_periods: vec![],
+            }
+        }
+    };
+
+    let periods = (last.0 - first.0) / period;
+
+    let missed_periods = (0..periods)
+        .map(|index| {
+            let start = Timestamp(first.0 + index * period);
+            Period {
+                start,
+                end: Timestamp(start.0 + period),
+            }
+        })
+        .filter(|p| received_at(&history, p.end) <= received_at(&history, p.start))
+        .collect();
+
+    AdherenceReport {
+        periods,
+        missed_periods,
+    }
+}
+
+/// Expects history sorted by timestamp
+fn received_at(history: &[(Timestamp, FundsAmount)], time: Timestamp) -> u64 {
+    history
+        .iter()
+        .take_while(|(timestamp, _)| *timestamp <= time)
+        .last()
+        .map(|(_, received)| received.val())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{distribution_adherence, Period};
+    use crate::models::{funds::FundsAmount, timestamp::Timestamp};
+    use anyhow::Result;
+    use std::time::Duration;
+
+    #[test]
+    fn test_fully_adherent_history() -> Result<()> {
+        let history = vec![
+            (Timestamp(0), FundsAmount::new(0)),
+            (Timestamp(10), FundsAmount::new(100)),
+            (Timestamp(20), FundsAmount::new(150)),
+            (Timestamp(30), FundsAmount::new(300)),
+        ];
+
+        let report = distribution_adherence(&history, Duration::from_secs(10));
+
+        assert_eq!(3, report.periods);
+        assert!(report.is_adherent());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_flags_missed_period() -> Result<()> {
+        // unsorted on purpose, and with an entry in the middle of a period
+        let history = vec![
+            (Timestamp(30), FundsAmount::new(200)),
+            (Timestamp(0), FundsAmount::new(0)),
+            (Timestamp(10), FundsAmount::new(100)),
+            (Timestamp(15), FundsAmount::new(100)),
+        ];
+
+        let report = distribution_adherence(&history, Duration::from_secs(10));
+
+        assert_eq!(3, report.periods);
+        assert_eq!(
+            vec![Period {
+                start: Timestamp(10),
+                end: Timestamp(20)
+            }],
+            report.missed_periods
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_incomplete_period_not_evaluated() -> Result<()> {
+        let history = vec![
+            (Timestamp(0), FundsAmount::new(0)),
+            (Timestamp(10), FundsAmount::new(100)),
+            (Timestamp(15), FundsAmount::new(100)),
+        ];
+
+        let report = distribution_adherence(&history, Duration::from_secs(10));
+
+        assert_eq!(1, report.periods);
+        assert!(report.is_adherent());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_empty_history() -> Result<()> {
+        let report = distribution_adherence(&[], Duration::from_secs(10));
+
+        assert_eq!(0, report.periods);
+        assert!(report.is_adherent());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,225 @@
+use crate::{
+    models::{dao_app_id::DaoAppId, timestamp::Timestamp},
+    state::dao_app_state::CentralAppGlobalState,
+};
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use std::convert::TryInto;
+
+/// A DAO's global state as a flat row, for columnar storage.
+/// Optional values are nullable, amounts u64, timestamps unix epoch seconds (i64) and durations seconds.
+///
+/// The field order is part of the schema: only append new fields at the end.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlatDaoRecord {
+    pub app_id: u64,
+    pub received: u64,
+    pub available: u64,
+    pub app_approval_version: u32,
+    pub app_clear_version: u32,
+    pub funds_asset_id: u64,
+    pub shares_asset_id: u64,
+    pub project_name: String,
+    pub project_desc_url: Option<String>,
+    pub share_price: u64,
+    pub investors_share: f64,
+    pub image_nft_asset_id: Option<u64>,
+    pub image_nft_url: Option<String>,
+    pub social_media_url: String,
+    pub prospectus_url: Option<String>,
+    pub prospectus_hash: Option<String>,
+    pub owner: String,
+    pub locked_shares: u64,
+    pub min_funds_target: u64,
+    pub min_funds_target_end_date: i64,
+    pub raised: u64,
+    pub setup_date: i64,
+    pub min_invest_amount: u64,
+    pub max_invest_amount: u64,
+    pub team_url: Option<String>,
+    pub lockup_end: Option<i64>,
+    pub voting_asset_id: Option<u64>,
+    pub min_hold_period: Option<u64>,
+    pub claim_deadline: Option<i64>,
+}
+
+pub fn flatten_dao_states(states: &[(DaoAppId, CentralAppGlobalState)]) -> Vec<FlatDaoRecord> {
+    states
+        .iter()
+        .map(|(app_id, state)| flatten_dao_state(*app_id, state))
+        .collect()
+}
+
+fn flatten_dao_state(app_id: DaoAppId, state: &CentralAppGlobalState) -> FlatDaoRecord {
+    // destructured, so adding a field to the state doesn't compile until it's added here
+    let CentralAppGlobalState {
+        received,
+        available,
+        app_approval_version,
+        app_clear_version,
+        funds_asset_id,
+        shares_asset_id,
+        project_name,
+        project_desc_url,
+        share_price,
+        investors_share,
+        image_nft,
+        social_media_url,
+        prospectus,
+        owner,
+        locked_shares,
+        min_funds_target,
+        min_funds_target_end_date,
+        raised,
+        setup_date,
+        min_invest_amount,
+        max_invest_amount,
+        team_url,
+        lockup_end,
+        voting_asset_id,
+        min_hold_period,
+        claim_deadline,
+    } = state;
+
+    FlatDaoRecord {
+        app_id: app_id.0,
+        received: received.val(),
+        available: available.val(),
+        app_approval_version: app_approval_version.0,
+        app_clear_version: app_clear_version.0,
+        funds_asset_id: funds_asset_id.0,
+        shares_asset_id: shares_asset_id.0,
+        project_name: project_name.clone(),
+        project_desc_url: project_desc_url.clone(),
+        share_price: share_price.val(),
+        // decimal to f64 doesn't fail
+        investors_share: investors_share.value().to_f64().unwrap_or_default(),
+        image_nft_asset_id: image_nft.as_ref().map(|nft| nft.asset_id),
+        image_nft_url: image_nft.as_ref().map(|nft| nft.url.clone()),
+        social_media_url: social_media_url.clone(),
+        prospectus_url: prospectus.as_ref().map(|p| p.url.clone()),
+        prospectus_hash: prospectus.as_ref().map(|p| p.hash.clone()),
+        owner: owner.to_string(),
+        locked_shares: locked_shares.val(),
+        min_funds_target: min_funds_target.val(),
+        min_funds_target_end_date: epoch(*min_funds_target_end_date),
+        raised: raised.val(),
+        setup_date: epoch(*setup_date),
+        min_invest_amount: min_invest_amount.val(),
+        max_invest_amount: max_invest_amount.val(),
+        team_url: team_url.clone(),
+        lockup_end: lockup_end.map(epoch),
+        voting_asset_id: *voting_asset_id,
+        min_hold_period: min_hold_period.map(|period| period.as_secs()),
+        claim_deadline: claim_deadline.map(epoch),
+    }
+}
+
+fn epoch(timestamp: Timestamp) -> i64 {
+    // saturate: timestamps beyond i64 (year ~292 billion) aren't meaningful
+    timestamp.0.try_into().unwrap_or(i64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{flatten_dao_states, FlatDaoRecord};
+    use crate::{
+        fixtures::funded_and_operating,
+        models::{nft::Nft, timestamp::Timestamp},
+    };
+    use anyhow::Result;
+    use std::time::Duration;
+
+    #[test]
+    fn test_flattened_record_represents_all_fields() -> Result<()> {
+        let snapshot = funded_and_operating();
+        let mut global = snapshot.global;
+        global.image_nft = Some(Nft {
+            url: "https://example.com/image".to_owned(),
+            asset_id: 789,
+        });
+        global.team_url = Some("https://example.com/team".to_owned());
+        global.lockup_end = Some(Timestamp(1_700_000_000));
+        global.voting_asset_id = Some(999);
+        global.min_hold_period = Some(Duration::from_secs(3600));
+        global.claim_deadline = Some(Timestamp(1_800_000_000));
+
+        let records = flatten_dao_states(&[(snapshot.app_id, global.clone())]);
+        assert_eq!(1, records.len());
+        let record = records[0].clone();
+
+        // round trip through serialization
+        let bytes = rmp_serde::to_vec_named(&record)?;
+        let deserialized: FlatDaoRecord = rmp_serde::from_slice(&bytes)?;
+        assert_eq!(record, deserialized);
+
+        // destructured, so a new field that's not checked here doesn't compile
+        let FlatDaoRecord {
+            app_id,
+            received,
+            available,
+            app_approval_version,
+            app_clear_version,
+            funds_asset_id,
+            shares_asset_id,
+            project_name,
+            project_desc_url,
+            share_price,
+            investors_share,
+            image_nft_asset_id,
+            image_nft_url,
+            social_media_url,
+            prospectus_url,
+            prospectus_hash,
+            owner,
+            locked_shares,
+            min_funds_target,
+            min_funds_target_end_date,
+            raised,
+            setup_date,
+            min_invest_amount,
+            max_invest_amount,
+            team_url,
+            lockup_end,
+            voting_asset_id,
+            min_hold_period,
+            claim_deadline,
+        } = deserialized;
+
+        let prospectus = global.prospectus.clone().unwrap();
+        assert_eq!(snapshot.app_id.0, app_id);
+        assert_eq!(global.received.val(), received);
+        assert_eq!(global.available.val(), available);
+        assert_eq!(global.app_approval_version.0, app_approval_version);
+        assert_eq!(global.app_clear_version.0, app_clear_version);
+        assert_eq!(global.funds_asset_id.0, funds_asset_id);
+        assert_eq!(global.shares_asset_id.0, shares_asset_id);
+        assert_eq!(global.project_name, project_name);
+        assert_eq!(global.project_desc_url, project_desc_url);
+        assert_eq!(global.share_price.val(), share_price);
+        assert_eq!(0.4, investors_share);
+        assert_eq!(Some(789), image_nft_asset_id);
+        assert_eq!(Some("https://example.com/image".to_owned()), image_nft_url);
+        assert_eq!(global.social_media_url, social_media_url);
+        assert_eq!(Some(prospectus.url), prospectus_url);
+        assert_eq!(Some(prospectus.hash), prospectus_hash);
+        assert_eq!(global.owner.to_string(), owner);
+        assert_eq!(global.locked_shares.val(), locked_shares);
+        assert_eq!(global.min_funds_target.val(), min_funds_target);
+        assert_eq!(
+            global.min_funds_target_end_date.0 as i64,
+            min_funds_target_end_date
+        );
+        assert_eq!(global.raised.val(), raised);
+        assert_eq!(global.setup_date.0 as i64, setup_date);
+        assert_eq!(global.min_invest_amount.val(), min_invest_amount);
+        assert_eq!(global.max_invest_amount.val(), max_invest_amount);
+        assert_eq!(global.team_url, team_url);
+        assert_eq!(Some(1_700_000_000), lockup_end);
+        assert_eq!(Some(999), voting_asset_id);
+        assert_eq!(Some(3600), min_hold_period);
+        assert_eq!(Some(1_800_000_000), claim_deadline);
+
+        Ok(())
+    }
+}
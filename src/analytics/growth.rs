@@ -0,0 +1,151 @@
+use crate::models::{dao_app_id::DaoAppId, timestamp::Timestamp};
+use anyhow::{anyhow, Result};
+use std::{collections::BTreeMap, time::Duration};
+
+/// Max number of buckets in the result, including the ones filled with count 0 between the first and last setup date
+const MAX_FILLED_BUCKETS: u64 = 10_000;
+
+/// Number of DAOs set up per `bucket` period, as (bucket start, count), sorted by time.
+///
+/// Buckets are aligned to multiples of `bucket` since the unix epoch, and go from the first to the last setup date,
+/// including buckets without DAOs (count 0), so the result can be plotted directly.
+/// Returns an error if that would be more than [MAX_FILLED_BUCKETS] buckets (a short `bucket`, or setup dates far apart).
+/// Empty if there are no DAOs or `bucket` is shorter than a second.
+pub fn growth_metrics(
+    daos: &[(DaoAppId, Timestamp /*setup_date*/)],
+    bucket: Duration,
+) -> Result<Vec<(Timestamp, u64)>> {
+    let bucket = bucket.as_secs();
+    if bucket == 0 {
+        return Ok(vec![]);
+    }
+
+    let mut counts: BTreeMap<u64, u64> = BTreeMap::new();
+    for (_, setup_date) in daos {
+        *counts.entry(setup_date.0 / bucket).or_default() += 1;
+    }
+
+    let (first, last) = match (counts.keys().next(), counts.keys().next_back()) {
+        (Some(first), Some(last)) => (*first, *last),
+        _ => return Ok(vec![]),
+    };
+
+    if last - first >= MAX_FILLED_BUCKETS {
+        return Err(anyhow!(
+            "Too many buckets: {} (max: {MAX_FILLED_BUCKETS}), use a longer bucket than {bucket}s",
+            // + 1 can overflow with the full range of timestamps
+            (last - first).saturating_add(1)
+        ));
+    }
+
+    Ok((first..=last)
+        .map(|index| {
+            (
+                Timestamp(index * bucket),
+                counts.get(&index).copied().unwrap_or(0),
+            )
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{growth_metrics, MAX_FILLED_BUCKETS};
+    use crate::models::{dao_app_id::DaoAppId, timestamp::Timestamp};
+    use anyhow::Result;
+    use std::time::Duration;
+
+    const DAY: u64 = 24 * 60 * 60;
+
+    #[test]
+    fn test_empty_input() -> Result<()> {
+        assert!(growth_metrics(&[], Duration::from_secs(DAY))?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_single_bucket() -> Result<()> {
+        let daos = vec![
+            (DaoAppId(1), Timestamp(10 * DAY + 100)),
+            (DaoAppId(2), Timestamp(10 * DAY + 200)),
+        ];
+
+        let metrics = growth_metrics(&daos, Duration::from_secs(DAY))?;
+
+        assert_eq!(vec![(Timestamp(10 * DAY), 2)], metrics);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_daos_spanning_multiple_buckets() -> Result<()> {
+        // unsorted, with a day without DAOs
+        let daos = vec![
+            (DaoAppId(1), Timestamp(13 * DAY + 5)),
+            (DaoAppId(2), Timestamp(10 * DAY)),
+            (DaoAppId(3), Timestamp(11 * DAY - 1)),
+            (DaoAppId(4), Timestamp(11 * DAY)),
+            (DaoAppId(5), Timestamp(13 * DAY)),
+        ];
+
+        let metrics = growth_metrics(&daos, Duration::from_secs(DAY))?;
+
+        assert_eq!(
+            vec![
+                (Timestamp(10 * DAY), 2),
+                (Timestamp(11 * DAY), 1),
+                (Timestamp(12 * DAY), 0),
+                (Timestamp(13 * DAY), 2),
+            ],
+            metrics
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fills_up_to_max_buckets() -> Result<()> {
+        // 1 second buckets, the setup dates exactly MAX_FILLED_BUCKETS buckets apart (counting the first and last)
+        let daos = vec![
+            (DaoAppId(1), Timestamp(10 * DAY)),
+            (DaoAppId(2), Timestamp(10 * DAY)),
+            (DaoAppId(3), Timestamp(10 * DAY + MAX_FILLED_BUCKETS - 1)),
+        ];
+
+        let metrics = growth_metrics(&daos, Duration::from_secs(1))?;
+
+        assert_eq!(MAX_FILLED_BUCKETS as usize, metrics.len());
+        assert_eq!((Timestamp(10 * DAY), 2), metrics[0]);
+        assert_eq!((Timestamp(10 * DAY + 1), 0), metrics[1]);
+        assert_eq!(
+            (Timestamp(10 * DAY + MAX_FILLED_BUCKETS - 1), 1),
+            metrics[metrics.len() - 1]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fails_with_too_many_buckets() -> Result<()> {
+        let one_too_many = vec![
+            (DaoAppId(1), Timestamp(10 * DAY)),
+            (DaoAppId(2), Timestamp(10 * DAY + MAX_FILLED_BUCKETS)),
+        ];
+        assert!(growth_metrics(&one_too_many, Duration::from_secs(1)).is_err());
+
+        let full_range = vec![
+            (DaoAppId(1), Timestamp(0)),
+            (DaoAppId(2), Timestamp(u64::MAX)),
+        ];
+        assert!(growth_metrics(&full_range, Duration::from_secs(1)).is_err());
+
+        // the same setup dates fit in longer buckets
+        assert_eq!(
+            vec![(Timestamp(10 * DAY), 2)],
+            growth_metrics(&one_too_many, Duration::from_secs(DAY))?
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,128 @@
+use crate::{
+    models::share_amount::ShareAmount,
+    state::{dao_app_state::CentralAppGlobalState, dividend::entitled_dividend},
+};
+use anyhow::{anyhow, Result};
+
+/// Dividend per share, for the income the DAO has received so far: the dividend of all the shares
+/// (`received * investors share`, see [entitled_dividend]) divided by the shares asset's supply (`total_shares`).
+pub fn dividend_per_share(
+    global: &CentralAppGlobalState,
+    total_shares: ShareAmount,
+) -> Result<f64> {
+    let dividend = entitled_dividend(global, total_shares, total_shares)?;
+    Ok(dividend.val() as f64 / total_shares.val() as f64)
+}
+
+/// Dividend an investor that has `current_locked` shares gets from locking 1 more share, for the income the DAO has received so far:
+/// the increase of the investor's entitled dividend (see [entitled_dividend]).
+///
+/// Dilution assumption: there's none from locking. The entitlement is relative to the shares asset's supply (`total_shares`), which doesn't change,
+/// so locking the share doesn't dilute the investor's existing shares or the other holders' shares.
+/// The result differs from [dividend_per_share] only because the entitled amounts are rounded down (to the funds asset's base units).
+/// Errors if `total_shares` is 0 or there's no share left to lock (`current_locked` is the supply).
+pub fn marginal_dividend_per_share(
+    global: &CentralAppGlobalState,
+    current_locked: ShareAmount,
+    total_shares: ShareAmount,
+) -> Result<f64> {
+    let locked_after = ShareAmount::new(
+        current_locked
+            .val()
+            .checked_add(1)
+            .ok_or_else(|| anyhow!("Failed: {current_locked} + 1"))?,
+    );
+    let before = entitled_dividend(global, current_locked, total_shares)?;
+    let after = entitled_dividend(global, locked_after, total_shares)?;
+    Ok((after.val() - before.val()) as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{dividend_per_share, marginal_dividend_per_share};
+    use crate::{
+        fixtures::{global_state, percentage},
+        models::{funds::FundsAmount, share_amount::ShareAmount},
+        state::dao_app_state::CentralAppGlobalState,
+    };
+    use anyhow::Result;
+
+    fn global(locked_shares: u64) -> CentralAppGlobalState {
+        let mut global = global_state();
+        global.received = FundsAmount::new(1000);
+        global.investors_share = percentage("0.5");
+        global.locked_shares = ShareAmount::new(locked_shares);
+        global
+    }
+
+    #[test]
+    fn test_marginal_dividend_equals_dividend_per_share() -> Result<()> {
+        let total = ShareAmount::new(100);
+        let global = global(100);
+
+        // 500 / 100
+        assert_eq!(5.0, dividend_per_share(&global, total)?);
+        assert_eq!(
+            5.0,
+            marginal_dividend_per_share(&global, ShareAmount::new(0), total)?
+        );
+        assert_eq!(
+            5.0,
+            marginal_dividend_per_share(&global, ShareAmount::new(50), total)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_marginal_dividend_not_diluted_by_locked_shares() -> Result<()> {
+        let total = ShareAmount::new(100);
+        for locked in [0, 10, 100] {
+            assert_eq!(
+                5.0,
+                marginal_dividend_per_share(&global(locked), ShareAmount::new(10), total)?
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_marginal_dividend_rounds_down_entitled_amounts() -> Result<()> {
+        let total = ShareAmount::new(3);
+        let global = global(0);
+
+        // 500 / 3 = 166.66..: the entitled dividend of 1, 2, 3 shares is 166, 333, 500
+        assert_eq!(
+            166.0,
+            marginal_dividend_per_share(&global, ShareAmount::new(0), total)?
+        );
+        assert_eq!(
+            167.0,
+            marginal_dividend_per_share(&global, ShareAmount::new(1), total)?
+        );
+        assert_eq!(
+            167.0,
+            marginal_dividend_per_share(&global, ShareAmount::new(2), total)?
+        );
+        assert!((dividend_per_share(&global, total)? - 166.6666666666).abs() < 1e-9);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_marginal_dividend_errors_with_invalid_shares() -> Result<()> {
+        let global = global(10);
+        // no share left to lock
+        assert!(
+            marginal_dividend_per_share(&global, ShareAmount::new(10), ShareAmount::new(10))
+                .is_err()
+        );
+        assert!(
+            marginal_dividend_per_share(&global, ShareAmount::new(0), ShareAmount::new(0)).is_err()
+        );
+        assert!(dividend_per_share(&global, ShareAmount::new(0)).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,9 @@
+pub mod break_even;
+pub mod compliance;
+pub mod concentration;
+pub mod distribution_adherence;
+pub mod flat_export;
+pub mod growth;
+pub mod marginal_dividend;
+pub mod prospectus_audit;
+pub mod refund_reserve;
@@ -0,0 +1,99 @@
+use crate::{
+    models::dao_app_id::DaoAppId,
+    state::dao_app_state::{CentralAppGlobalState, Prospectus, ProspectusVerifyResult},
+    util::ipfs::IpfsConfig,
+};
+use futures::{stream, StreamExt};
+use std::{future::Future, time::Duration};
+
+/// Fetches the prospectus of each DAO and verifies it against the hash stored in its state, with at most `concurrency` requests at a time.
+/// Meant to be run periodically, to detect prospectuses that were tampered with or lost.
+///
+/// There's a result for each DAO, in the same order as `states`: DAOs without a prospectus are [ProspectusVerifyResult::NoProspectus], without fetching anything.
+/// A fetch that doesn't complete within `timeout` is [ProspectusVerifyResult::Unreachable], so an unresponsive gateway doesn't stall the audit.
+pub async fn verify_all_prospectuses(
+    states: &[(DaoAppId, CentralAppGlobalState)],
+    http: &reqwest::Client,
+    ipfs: &IpfsConfig,
+    concurrency: usize,
+    timeout: Duration,
+) -> Vec<(DaoAppId, ProspectusVerifyResult)> {
+    verify_all_with(states, concurrency, |prospectus| {
+        prospectus.fetch_and_verify(http, ipfs, timeout)
+    })
+    .await
+}
+
+async fn verify_all_with<'a, F, Fut>(
+    states: &'a [(DaoAppId, CentralAppGlobalState)],
+    concurrency: usize,
+    verify: F,
+) -> Vec<(DaoAppId, ProspectusVerifyResult)>
+where
+    F: Fn(&'a Prospectus) -> Fut,
+    Fut: Future<Output = ProspectusVerifyResult>,
+{
+    stream::iter(states)
+        .map(|(app_id, state)| {
+            let verified = state.prospectus.as_ref().map(&verify);
+            async move {
+                let result = match verified {
+                    Some(verified) => verified.await,
+                    None => ProspectusVerifyResult::NoProspectus,
+                };
+                (*app_id, result)
+            }
+        })
+        // buffered with 0 would never make progress
+        .buffered(concurrency.max(1))
+        .collect()
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::verify_all_with;
+    use crate::{
+        fixtures::global_state,
+        models::dao_app_id::DaoAppId,
+        state::dao_app_state::{Prospectus, ProspectusVerifyResult},
+        util::network_util::sleep,
+    };
+    use anyhow::Result;
+    use futures::executor::block_on;
+
+    #[test]
+    fn test_results_for_all_daos_in_order() -> Result<()> {
+        let with_prospectus = |url: &str| {
+            let mut state = global_state();
+            state.prospectus = Some(Prospectus::new(b"document", url.to_owned()));
+            state
+        };
+        let states = vec![
+            (DaoAppId(1), with_prospectus("slow")),
+            (DaoAppId(2), global_state()),
+            (DaoAppId(3), with_prospectus("fast")),
+        ];
+
+        let results = block_on(verify_all_with(&states, 2, |prospectus| async move {
+            // the first one finishes last
+            if prospectus.url == "slow" {
+                sleep(50).await;
+                ProspectusVerifyResult::Mismatch
+            } else {
+                ProspectusVerifyResult::Ok
+            }
+        }));
+
+        assert_eq!(
+            vec![
+                (DaoAppId(1), ProspectusVerifyResult::Mismatch),
+                (DaoAppId(2), ProspectusVerifyResult::NoProspectus),
+                (DaoAppId(3), ProspectusVerifyResult::Ok),
+            ],
+            results
+        );
+
+        Ok(())
+    }
+}
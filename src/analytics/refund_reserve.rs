@@ -0,0 +1,102 @@
+use crate::{models::funds::FundsAmount, state::dao_app_state::CentralAppGlobalState};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefundReserve {
+    /// The raise reached the min target, so investors can't be refunded
+    NotRequired,
+    /// The escrow can refund all the raised funds
+    Sufficient,
+    /// What's missing on the escrow to refund all the raised funds
+    Shortfall(FundsAmount),
+}
+
+/// Whether the escrow holds enough to refund the investors, in case the raise fails (doesn't reach the min target).
+/// Only applies while the raise hasn't reached the target.
+///
+/// The contract keeps the raised funds on the escrow until the target is reached, so a shortfall shouldn't happen:
+/// it indicates a serious bug.
+pub fn refund_reserve(
+    global: &CentralAppGlobalState,
+    escrow_balance: FundsAmount,
+) -> RefundReserve {
+    if global.min_funds_target_reached() {
+        return RefundReserve::NotRequired;
+    }
+
+    // everything raised is refundable
+    let refundable = global.raised.val();
+    if escrow_balance.val() >= refundable {
+        RefundReserve::Sufficient
+    } else {
+        RefundReserve::Shortfall(FundsAmount::new(refundable - escrow_balance.val()))
+    }
+}
+
+/// See [refund_reserve]
+pub fn refund_reserve_sufficient(
+    global: &CentralAppGlobalState,
+    escrow_balance: FundsAmount,
+) -> bool {
+    !matches!(
+        refund_reserve(global, escrow_balance),
+        RefundReserve::Shortfall(_)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{refund_reserve, refund_reserve_sufficient, RefundReserve};
+    use crate::{
+        fixtures::global_state, models::funds::FundsAmount,
+        state::dao_app_state::CentralAppGlobalState,
+    };
+    use anyhow::Result;
+
+    fn failing_raise() -> CentralAppGlobalState {
+        let mut global = global_state();
+        global.min_funds_target = FundsAmount::new(1000);
+        global.raised = FundsAmount::new(600);
+        global
+    }
+
+    #[test]
+    fn test_sufficient_if_escrow_has_raised_funds() -> Result<()> {
+        let global = failing_raise();
+
+        assert_eq!(
+            RefundReserve::Sufficient,
+            refund_reserve(&global, FundsAmount::new(600))
+        );
+        assert!(refund_reserve_sufficient(&global, FundsAmount::new(600)));
+        assert!(refund_reserve_sufficient(&global, FundsAmount::new(700)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_shortfall_if_escrow_has_less_than_raised() -> Result<()> {
+        let global = failing_raise();
+
+        assert_eq!(
+            RefundReserve::Shortfall(FundsAmount::new(100)),
+            refund_reserve(&global, FundsAmount::new(500))
+        );
+        assert!(!refund_reserve_sufficient(&global, FundsAmount::new(500)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_not_required_if_target_reached() -> Result<()> {
+        let mut global = failing_raise();
+        global.raised = global.min_funds_target;
+
+        assert_eq!(
+            RefundReserve::NotRequired,
+            refund_reserve(&global, FundsAmount::new(0))
+        );
+        assert!(refund_reserve_sufficient(&global, FundsAmount::new(0)));
+
+        Ok(())
+    }
+}
@@ -7,10 +7,37 @@ use algonaut::{
 };
 use anyhow::{Error, Result};
 use serde::{Deserialize, Serialize};
-use std::convert::TryInto;
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+use std::{
+    convert::TryInto,
+    fmt::{self, Display, Formatter},
+};
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct Version(pub u32);
 
+/// The contract version isn't in the range the client supports
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionMismatch {
+    pub actual: Version,
+    pub min_supported: Version,
+    pub max_supported: Version,
+}
+
+impl Display for VersionMismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Unsupported contract version: {}. Supported: {}..={}",
+            self.actual.0, self.min_supported.0, self.max_supported.0
+        )
+    }
+}
+
+impl From<VersionMismatch> for Error {
+    fn from(err: VersionMismatch) -> Self {
+        Error::msg(err.to_string())
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Versions {
     pub app_approval: Version,
@@ -0,0 +1,400 @@
+use crate::{
+    api::version::Version,
+    models::{
+        asset_amount::AssetAmount,
+        dao_app_id::DaoAppId,
+        funds::{FundsAmount, FundsAssetId},
+        share_amount::{ShareAmount, SharesAssetId},
+        shares_percentage::SharesPercentage,
+        timestamp::Timestamp,
+    },
+    state::{
+        app_state::{ApplicationGlobalState, ApplicationLocalStateError},
+        dao_app_state::{
+            CentralAppGlobalState, CentralAppInvestorState, DaoGlobalStateError, Prospectus,
+            SignedProspectus, LOCAL_SCHEMA_NUM_BYTE_SLICES, LOCAL_SCHEMA_NUM_INTS,
+        },
+        dao_snapshot::DaoSnapshot,
+        state_source::{GlobalStateSource, LocalStateSource},
+    },
+};
+use algonaut::{
+    core::Address,
+    model::algod::v2::{ApplicationLocalState, ApplicationStateSchema},
+};
+use anyhow::Result;
+use async_trait::async_trait;
+use rust_decimal::Decimal;
+use std::convert::TryInto;
+
+const DAY: u64 = 24 * 60 * 60;
+
+/// Setup date of the scenario DAOs
+pub const SETUP_DATE: Timestamp = Timestamp(1_650_000_000);
+/// "Now" for the scenarios: 60 days after setup. The raise of the scenario DAOs ends after 30 days.
+pub const NOW: Timestamp = Timestamp(SETUP_DATE.0 + 60 * DAY);
+
+/// 1 funds asset unit (6 decimals)
+const UNIT: u64 = 1_000_000;
+
+/// Total supply of the scenario DAOs' shares: the dividends are relative to it, not to the locked shares.
+/// At most 12_000 are sold in the raises, the rest stay with the DAO.
+pub const SHARE_SUPPLY: ShareAmount = ShareAmount(AssetAmount(24_000));
+
+/// A set up DAO without optional data, income or investors - tests modify the fields they need
+pub fn global_state() -> CentralAppGlobalState {
+    CentralAppGlobalState {
+        received: FundsAmount::new(0),
+        available: FundsAmount::new(0),
+        app_approval_version: Version(1),
+        app_clear_version: Version(1),
+        funds_asset_id: FundsAssetId(123),
+        shares_asset_id: SharesAssetId(456),
+        project_name: "my dao".to_owned(),
+        project_desc_url: None,
+        share_price: FundsAmount::new(10),
+        investors_share: percentage("0.4"),
+        image_nft: None,
+        social_media_url: "".to_owned(),
+        prospectus: None,
+        owner: Address([0; 32]),
+        locked_shares: ShareAmount::new(0),
+        min_funds_target: FundsAmount::new(0),
+        min_funds_target_end_date: Timestamp(0),
+        raised: FundsAmount::new(0),
+        setup_date: Timestamp(0),
+        min_invest_amount: ShareAmount::new(1),
+        max_invest_amount: ShareAmount::new(0),
+        team_url: None,
+        lockup_end: None,
+        voting_asset_id: None,
+        min_hold_period: None,
+        claim_deadline: None,
+    }
+}
+
+/// An investor that has locked `shares`, without having claimed anything
+pub fn investor_state(shares: u64) -> CentralAppInvestorState {
+    CentralAppInvestorState {
+        shares: ShareAmount::new(shares),
+        claimed: FundsAmount::new(0),
+        claimed_init: FundsAmount::new(0),
+        signed_prospectus: None,
+    }
+}
+
+pub fn percentage(str: &str) -> SharesPercentage {
+    str.parse::<Decimal>().unwrap().try_into().unwrap()
+}
+
+/// State source with in-memory state, encoded like algod returns it, to exercise the parsing without a node
+#[derive(Debug, Clone, Default)]
+pub struct MockStateSource {
+    global_states: Vec<(DaoAppId, Address, ApplicationGlobalState)>,
+    local_states: Vec<(DaoAppId, Address, ApplicationLocalState)>,
+}
+
+impl MockStateSource {
+    pub fn with_dao(mut self, app_id: DaoAppId, global: &CentralAppGlobalState) -> Result<Self> {
+        self.global_states.push((
+            app_id,
+            global.owner,
+            ApplicationGlobalState(global.to_teal_key_values()?),
+        ));
+        Ok(self)
+    }
+
+    pub fn with_investor(
+        mut self,
+        app_id: DaoAppId,
+        investor: Address,
+        state: &CentralAppInvestorState,
+    ) -> Self {
+        self.local_states.push((
+            app_id,
+            investor,
+            ApplicationLocalState {
+                id: app_id.0,
+                key_value: state.to_teal_key_values(),
+                schema: ApplicationStateSchema {
+                    num_byte_slice: LOCAL_SCHEMA_NUM_BYTE_SLICES,
+                    num_uint: LOCAL_SCHEMA_NUM_INTS,
+                },
+            },
+        ));
+        self
+    }
+
+    /// An account's raw local state, e.g. only opted in or malformed
+    pub fn with_local_state(
+        mut self,
+        app_id: DaoAppId,
+        address: Address,
+        state: ApplicationLocalState,
+    ) -> Self {
+        self.local_states.push((app_id, address, state));
+        self
+    }
+
+    /// The DAO and all its investors
+    pub fn from_snapshot(snapshot: &DaoSnapshot) -> Result<Self> {
+        let mut source = MockStateSource::default().with_dao(snapshot.app_id, &snapshot.global)?;
+        for (investor, state) in &snapshot.investors {
+            source = source.with_investor(snapshot.app_id, *investor, state);
+        }
+        Ok(source)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl GlobalStateSource for MockStateSource {
+    async fn global_state(
+        &self,
+        app_id: DaoAppId,
+    ) -> Result<(Address, ApplicationGlobalState), DaoGlobalStateError> {
+        self.global_states
+            .iter()
+            .find(|(id, _, _)| *id == app_id)
+            .map(|(_, owner, gs)| (*owner, gs.clone()))
+            .ok_or_else(|| DaoGlobalStateError::Msg(format!("App not found: {app_id:?}")))
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl LocalStateSource for MockStateSource {
+    async fn local_state(
+        &self,
+        address: &Address,
+        app_id: DaoAppId,
+    ) -> Result<ApplicationLocalState, ApplicationLocalStateError<'static>> {
+        self.local_states
+            .iter()
+            .find(|(id, investor, _)| *id == app_id && investor == address)
+            .map(|(_, _, state)| state.clone())
+            .ok_or(ApplicationLocalStateError::NotOptedIn)
+    }
+}
+
+/// Just set up: no investors, nothing raised or received.
+/// Share price: 1 unit, min target: 10_000 units, ending 30 days after setup, investors get 40% of the income.
+pub fn fresh_setup() -> DaoSnapshot {
+    DaoSnapshot {
+        app_id: DaoAppId(1000),
+        global: scenario_global_state(),
+        investors: vec![],
+    }
+}
+
+/// Raise in progress: 2 investors with 2_000 shares each, so 4_000 of 10_000 units raised.
+/// Investors signed the prospectus when investing.
+pub fn mid_raise() -> DaoSnapshot {
+    let mut global = scenario_global_state();
+    global.raised = FundsAmount::new(4_000 * UNIT);
+    global.locked_shares = ShareAmount::new(4_000);
+
+    DaoSnapshot {
+        app_id: DaoAppId(1001),
+        global,
+        investors: vec![
+            (investor_address(1), signed_investor_state(2_000)),
+            (investor_address(2), signed_investor_state(2_000)),
+        ],
+    }
+}
+
+/// Raise succeeded (12_000 units with 3 investors) and the DAO has received 5_000 units of income, of which the shareholders get 2_000:
+/// the investors, with 12_000 of the [SHARE_SUPPLY] shares, are entitled to 1_000.
+/// The first investor claimed half of their dividend, the others nothing yet.
+pub fn funded_and_operating() -> DaoSnapshot {
+    let mut global = funded_global_state();
+    global.received = FundsAmount::new(5_000 * UNIT);
+    global.available = FundsAmount::new(5_000 * UNIT);
+
+    // dividend: 2_000 units * 6_000 / 24_000 = 500 units
+    let mut first = signed_investor_state(6_000);
+    first.claimed = FundsAmount::new(250 * UNIT);
+
+    DaoSnapshot {
+        app_id: DaoAppId(1002),
+        global,
+        investors: vec![
+            (investor_address(1), first),
+            (investor_address(2), signed_investor_state(3_000)),
+            (investor_address(3), signed_investor_state(3_000)),
+        ],
+    }
+}
+
+/// The raise ended ([NOW] is past the target end date) with 3_000 of the 10_000 units target: investors can get a refund.
+pub fn failed_raise() -> DaoSnapshot {
+    let mut global = scenario_global_state();
+    global.raised = FundsAmount::new(3_000 * UNIT);
+    global.locked_shares = ShareAmount::new(3_000);
+
+    DaoSnapshot {
+        app_id: DaoAppId(1003),
+        global,
+        investors: vec![
+            (investor_address(1), signed_investor_state(1_000)),
+            (investor_address(2), signed_investor_state(2_000)),
+        ],
+    }
+}
+
+/// Like [funded_and_operating], but all the investors claimed their entire dividend
+/// and the claimed funds left the escrow.
+pub fn fully_claimed() -> DaoSnapshot {
+    let mut global = funded_global_state();
+    global.received = FundsAmount::new(5_000 * UNIT);
+    // 1_000 units were claimed (2_000 units * 12_000 / 24_000), the rest is the DAO's part and the dividend of the shares it holds
+    global.available = FundsAmount::new(4_000 * UNIT);
+
+    let claimed = |shares: u64, claimed_units: u64| {
+        let mut state = signed_investor_state(shares);
+        state.claimed = FundsAmount::new(claimed_units * UNIT);
+        state
+    };
+
+    DaoSnapshot {
+        app_id: DaoAppId(1004),
+        global,
+        investors: vec![
+            (investor_address(1), claimed(6_000, 500)),
+            (investor_address(2), claimed(3_000, 250)),
+            (investor_address(3), claimed(3_000, 250)),
+        ],
+    }
+}
+
+fn scenario_global_state() -> CentralAppGlobalState {
+    let mut global = global_state();
+    global.project_name = "Coffee shop".to_owned();
+    global.project_desc_url = Some("https://example.com/dao/desc".to_owned());
+    global.share_price = FundsAmount::new(UNIT);
+    global.investors_share = percentage("0.4");
+    global.social_media_url = "https://twitter.com/coffee".to_owned();
+    global.prospectus = Some(prospectus());
+    global.owner = Address([100; 32]);
+    global.min_funds_target = FundsAmount::new(10_000 * UNIT);
+    global.min_funds_target_end_date = Timestamp(SETUP_DATE.0 + 30 * DAY);
+    global.setup_date = SETUP_DATE;
+    global.max_invest_amount = ShareAmount::new(10_000);
+    global
+}
+
+fn funded_global_state() -> CentralAppGlobalState {
+    let mut global = scenario_global_state();
+    global.raised = FundsAmount::new(12_000 * UNIT);
+    global.locked_shares = ShareAmount::new(12_000);
+    global
+}
+
+fn prospectus() -> Prospectus {
+    Prospectus::new(
+        b"prospectus document",
+        "https://example.com/dao/prospectus.pdf".to_owned(),
+    )
+}
+
+fn signed_investor_state(shares: u64) -> CentralAppInvestorState {
+    let prospectus = prospectus();
+    let mut state = investor_state(shares);
+    state.signed_prospectus = Some(SignedProspectus {
+        hash: prospectus.hash,
+        url: prospectus.url,
+        timestamp: Timestamp(SETUP_DATE.0 + DAY),
+    });
+    state
+}
+
+fn investor_address(index: u8) -> Address {
+    Address([index; 32])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        failed_raise, fresh_setup, fully_claimed, funded_and_operating, mid_raise, MockStateSource,
+        SHARE_SUPPLY,
+    };
+    use crate::{
+        models::dao_app_id::DaoAppId,
+        state::dao_app_state::{dao_global_state_from_source, dao_investor_state_from_source},
+    };
+    use algonaut::core::Address;
+    use anyhow::Result;
+    use futures::executor::block_on;
+
+    #[test]
+    fn test_scenarios_are_consistent() -> Result<()> {
+        for snapshot in [
+            fresh_setup(),
+            mid_raise(),
+            funded_and_operating(),
+            failed_raise(),
+            fully_claimed(),
+        ] {
+            let global = &snapshot.global;
+            let investor_shares: u64 = snapshot
+                .investors
+                .iter()
+                .map(|(_, state)| state.shares.val())
+                .sum();
+
+            assert_eq!(global.locked_shares.val(), investor_shares);
+            assert!(investor_shares <= SHARE_SUPPLY.val());
+            assert_eq!(
+                global.raised.val(),
+                investor_shares * global.share_price.val()
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reads_state_from_mock_source() -> Result<()> {
+        let snapshot = funded_and_operating();
+        let source = MockStateSource::from_snapshot(&snapshot)?;
+
+        let global = block_on(dao_global_state_from_source(&source, snapshot.app_id))?;
+        assert_eq!(snapshot.global, global);
+
+        for (investor, state) in &snapshot.investors {
+            let read = block_on(dao_investor_state_from_source(
+                &source,
+                investor,
+                snapshot.app_id,
+            ))?;
+            assert_eq!(state, &read);
+        }
+
+        assert!(block_on(dao_global_state_from_source(&source, DaoAppId(1))).is_err());
+        assert!(block_on(dao_investor_state_from_source(
+            &source,
+            &Address([200; 32]),
+            snapshot.app_id
+        ))
+        .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fully_claimed_investors_cant_claim() -> Result<()> {
+        let snapshot = fully_claimed();
+
+        for (_, state) in &snapshot.investors {
+            assert_eq!(
+                0,
+                state
+                    .claimable_dividend(&snapshot.global, SHARE_SUPPLY)?
+                    .val()
+            );
+        }
+
+        Ok(())
+    }
+}
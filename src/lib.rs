@@ -1,7 +1,11 @@
+pub mod analytics;
 pub mod api;
 pub mod checked;
 pub mod date_util;
 pub mod dependencies;
+/// Ready-made state for tests, which don't need a node. Available to other crates with the `test-util` feature.
+#[cfg(any(test, feature = "test-util"))]
+pub mod fixtures;
 pub mod logger;
 pub mod models;
 pub mod state;
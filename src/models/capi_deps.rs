@@ -1,5 +1,13 @@
-use super::shares_percentage::SharesPercentage;
+use super::{funds::FundsAmount, shares_percentage::SharesPercentage};
 use algonaut::core::Address;
+use anyhow::{anyhow, Result};
+use rust_decimal::Decimal;
+use std::convert::TryInto;
+
+/// The fee Capi charges on customer payments (3%). It has to match the fee of the deployed contract,
+/// and environments are configured with it in [CapiAssetDaoDeps::escrow_percentage].
+pub const CAPI_FEE_PERCENTAGE: SharesPercentage =
+    SharesPercentage::from_decimal_unchecked(Decimal::from_parts(3, 0, 0, false, 2));
 
 /// Capi asset environment relevant to the DAOs
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -9,5 +17,106 @@ pub struct CapiAssetDaoDeps {
     pub address: CapiAddress,
 }
 
+impl CapiAssetDaoDeps {
+    /// Fee charged on a customer payment of `amount`, with this environment's fee percentage (see [capi_fee])
+    pub fn capi_fee(&self, amount: FundsAmount) -> Result<FundsAmount> {
+        capi_fee(amount, self.escrow_percentage)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CapiAddress(pub Address);
+
+/// Fee charged on a customer payment of `amount`, when draining: `amount * fee percentage`, rounded down, like in TEAL (integer division).
+/// The DAO's `received` is incremented with the rest (`amount - fee`).
+///
+/// `fee_percentage` is normally [CAPI_FEE_PERCENTAGE] (see [CapiAssetDaoDeps::capi_fee]).
+pub fn capi_fee(amount: FundsAmount, fee_percentage: SharesPercentage) -> Result<FundsAmount> {
+    let fee = amount.val() as u128 * fee_percentage.to_u64()? as u128
+        / SharesPercentage::integer_precision() as u128;
+    // the percentage is <= 1, so the fee is <= amount
+    Ok(FundsAmount::new(fee.try_into().map_err(|e| {
+        anyhow!("Unexpected: fee: {fee} doesn't fit in u64: {e}")
+    })?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{capi_fee, CAPI_FEE_PERCENTAGE};
+    use crate::{
+        fixtures::percentage,
+        models::{funds::FundsAmount, shares_percentage::SharesPercentage},
+    };
+    use anyhow::Result;
+    use std::convert::TryInto;
+
+    #[test]
+    fn test_capi_fee_percentage_is_valid() -> Result<()> {
+        assert_eq!(percentage("0.03"), CAPI_FEE_PERCENTAGE);
+        // what the checked conversion accepts
+        let checked: SharesPercentage = CAPI_FEE_PERCENTAGE.value().try_into()?;
+        assert_eq!(checked, CAPI_FEE_PERCENTAGE);
+        assert_eq!(300, CAPI_FEE_PERCENTAGE.to_u64()?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_capi_fee_with_capi_fee_percentage() -> Result<()> {
+        // 333 * 0.03 = 9.99
+        assert_eq!(
+            FundsAmount::new(9),
+            capi_fee(FundsAmount::new(333), CAPI_FEE_PERCENTAGE)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_capi_fee_of_1_micro_unit_is_rounded_down_to_0() -> Result<()> {
+        assert_eq!(
+            FundsAmount::new(0),
+            capi_fee(FundsAmount::new(1), percentage("0.03"))?
+        );
+        assert_eq!(
+            FundsAmount::new(1),
+            capi_fee(FundsAmount::new(1), percentage("1"))?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_capi_fee_is_rounded_down() -> Result<()> {
+        // 333 * 0.03 = 9.99
+        assert_eq!(
+            FundsAmount::new(9),
+            capi_fee(FundsAmount::new(333), percentage("0.03"))?
+        );
+        // 1001 * 0.005 = 5.005
+        assert_eq!(
+            FundsAmount::new(5),
+            capi_fee(FundsAmount::new(1001), percentage("0.005"))?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_capi_fee_without_rounding() -> Result<()> {
+        assert_eq!(
+            FundsAmount::new(30_000),
+            capi_fee(FundsAmount::new(1_000_000), percentage("0.03"))?
+        );
+        assert_eq!(
+            FundsAmount::new(0),
+            capi_fee(FundsAmount::new(u64::MAX), percentage("0"))?
+        );
+        assert_eq!(
+            FundsAmount::new(u64::MAX),
+            capi_fee(FundsAmount::new(u64::MAX), percentage("1"))?
+        );
+
+        Ok(())
+    }
+}
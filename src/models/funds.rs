@@ -8,6 +8,9 @@ use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 
+/// Decimals of the funds asset (a stablecoin)
+pub const FUNDS_ASSET_DECIMALS: u32 = 6;
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct FundsAmount(pub AssetAmount);
 
@@ -33,6 +36,19 @@ impl FundsAmount {
     pub fn to_bytes(&self) -> Vec<u8> {
         self.0.to_bytes()
     }
+
+    /// The amount in asset units instead of base units, e.g. 1500000 with 6 decimals: "1.500000"
+    pub fn display_with_decimals(&self, decimals: u32) -> String {
+        let base_units = self.val().to_string();
+        let decimals = decimals as usize;
+        if decimals == 0 {
+            return base_units;
+        }
+        // pad with zeros, so there's at least a 0 before the decimal point
+        let padded = format!("{base_units:0>width$}", width = decimals + 1);
+        let (int, fraction) = padded.split_at(padded.len() - decimals);
+        format!("{int}.{fraction}")
+    }
 }
 
 impl CheckedAdd for FundsAmount {
@@ -75,3 +91,25 @@ pub struct Funds {
     pub asset_id: FundsAssetId,
     pub amount: FundsAmount,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{FundsAmount, FUNDS_ASSET_DECIMALS};
+    use anyhow::Result;
+
+    #[test]
+    fn test_display_with_decimals() -> Result<()> {
+        assert_eq!(
+            "1.500000",
+            FundsAmount::new(1_500_000).display_with_decimals(FUNDS_ASSET_DECIMALS)
+        );
+        assert_eq!(
+            "0.000012",
+            FundsAmount::new(12).display_with_decimals(FUNDS_ASSET_DECIMALS)
+        );
+        assert_eq!("0.0", FundsAmount::new(0).display_with_decimals(1));
+        assert_eq!("123", FundsAmount::new(123).display_with_decimals(0));
+
+        Ok(())
+    }
+}
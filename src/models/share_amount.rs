@@ -7,6 +7,22 @@ use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::fmt::Display;
 
+/// Id of the shares asset (DAO ASA)
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SharesAssetId(pub u64);
+
+impl SharesAssetId {
+    pub fn new(id: u64) -> SharesAssetId {
+        SharesAssetId(id)
+    }
+}
+
+impl From<SharesAssetId> for u64 {
+    fn from(id: SharesAssetId) -> Self {
+        id.0
+    }
+}
+
 /// An amount of shares (DAO ASA)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ShareAmount(pub AssetAmount);
@@ -37,6 +37,11 @@ impl TryFrom<Decimal> for SharesPercentage {
 }
 
 impl SharesPercentage {
+    /// For constants: the caller has to ensure that the value is in [0..1] and has max [MAX_DECIMALS] decimals
+    pub(crate) const fn from_decimal_unchecked(value: Decimal) -> SharesPercentage {
+        SharesPercentage(value)
+    }
+
     pub fn value(&self) -> Decimal {
         self.0
     }
@@ -47,8 +52,13 @@ impl SharesPercentage {
         (self.0 * multiplier).to_u64().ok_or_else(|| anyhow!("Invalid state: since we allow max {MAX_DECIMALS} digits, multiplying by {multiplier} should yield an integer"))
     }
 
+    /// Integer representation (see [Self::to_u64]) of 100%
+    pub fn integer_precision() -> u64 {
+        10u64.pow(MAX_DECIMALS)
+    }
+
     fn conversion_integer_multiplier() -> Decimal {
-        10u64.pow(MAX_DECIMALS).as_decimal()
+        Self::integer_precision().as_decimal()
     }
 }
 
@@ -1,11 +1,11 @@
 use std::convert::TryInto;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use chrono::{DateTime, NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 /// Unix timestamp (seconds)
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct Timestamp(pub u64);
 
 impl From<DateTime<Utc>> for Timestamp {
@@ -24,4 +24,44 @@ impl Timestamp {
     pub fn now() -> Timestamp {
         Utc::now().into()
     }
+
+    /// 8 bytes, big-endian: how TEAL stores a uint as bytes (`itob`)
+    pub fn to_bytes(&self) -> [u8; 8] {
+        self.0.to_be_bytes()
+    }
+
+    /// The inverse of [Self::to_bytes]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Timestamp> {
+        let array: [u8; 8] = bytes.try_into().map_err(|_| {
+            anyhow!(
+                "Invalid timestamp bytes: {bytes:?}. Expected 8 bytes (big-endian u64), got: {}",
+                bytes.len()
+            )
+        })?;
+        Ok(Timestamp(u64::from_be_bytes(array)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Timestamp;
+    use anyhow::Result;
+
+    #[test]
+    fn test_bytes_round_trip() -> Result<()> {
+        let timestamp = Timestamp(1_650_086_400);
+
+        assert_eq!(timestamp, Timestamp::from_bytes(&timestamp.to_bytes())?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_bytes_fails_with_wrong_length() -> Result<()> {
+        let err = Timestamp::from_bytes(&[1, 2, 3]).unwrap_err();
+
+        assert!(err.to_string().contains("Expected 8 bytes"));
+
+        Ok(())
+    }
 }
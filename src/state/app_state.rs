@@ -1,3 +1,4 @@
+use crate::util::retry::Retryable;
 use algonaut::{
     algod::v2::Algod,
     core::Address,
@@ -5,8 +6,9 @@ use algonaut::{
     model::algod::v2::{Account, ApplicationLocalState, TealKeyValue, TealValue},
 };
 use anyhow::{anyhow, Error, Result};
-use data_encoding::BASE64;
+use data_encoding::{BASE64, HEXLOWER};
 use std::{
+    collections::BTreeMap,
     convert::TryInto,
     fmt::{self, Display, Formatter},
 };
@@ -48,6 +50,11 @@ pub fn local_state_with_key(
 pub enum ApplicationLocalStateError<'a> {
     NotOptedIn,
     LocalStateNotFound(AppStateKey<'a>),
+    /// Error fetching the state, classified when converting it (see [Retryable])
+    Algod {
+        details: String,
+        retryable: bool,
+    },
     Msg(String),
 }
 
@@ -59,6 +66,30 @@ impl<'a> AppStateKey<'a> {
     pub fn to_teal_encoded_str(&self) -> String {
         BASE64.encode(self.0.as_bytes())
     }
+
+    /// Int key-value with this key, as returned by sdk
+    pub fn uint_key_value(&self, uint: u64) -> TealKeyValue {
+        TealKeyValue {
+            key: self.to_teal_encoded_str(),
+            value: TealValue {
+                value_type: 2,
+                bytes: vec![],
+                uint,
+            },
+        }
+    }
+
+    /// Bytes key-value with this key, as returned by sdk
+    pub fn bytes_key_value(&self, bytes: Vec<u8>) -> TealKeyValue {
+        TealKeyValue {
+            key: self.to_teal_encoded_str(),
+            value: TealValue {
+                value_type: 1,
+                bytes,
+                uint: 0,
+            },
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -140,7 +171,21 @@ impl<'a> From<anyhow::Error> for ApplicationLocalStateError<'a> {
 
 impl<'a> From<ServiceError> for ApplicationLocalStateError<'a> {
     fn from(e: ServiceError) -> Self {
-        Self::Msg(e.to_string())
+        Self::Algod {
+            retryable: e.is_retryable(),
+            details: e.to_string(),
+        }
+    }
+}
+
+impl<'a> Retryable for ApplicationLocalStateError<'a> {
+    fn is_retryable(&self) -> bool {
+        match self {
+            ApplicationLocalStateError::Algod { retryable, .. } => *retryable,
+            ApplicationLocalStateError::NotOptedIn
+            | ApplicationLocalStateError::LocalStateNotFound(_)
+            | ApplicationLocalStateError::Msg(_) => false,
+        }
     }
 }
 
@@ -150,6 +195,54 @@ impl From<ApplicationLocalStateError<'static>> for anyhow::Error {
     }
 }
 
+/// A decoded state value, for display
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StateValue {
+    Uint(u64),
+    /// Bytes that are neither an address nor text
+    Bytes(Vec<u8>),
+    Addr(Address),
+    Text(String),
+}
+
+impl Display for StateValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            StateValue::Uint(uint) => write!(f, "{uint}"),
+            StateValue::Bytes(bytes) => write!(f, "0x{}", HEXLOWER.encode(bytes)),
+            StateValue::Addr(address) => write!(f, "{address}"),
+            StateValue::Text(text) => write!(f, "{text:?}"),
+        }
+    }
+}
+
+/// Decodes the keys and values of any app's (global or local) state, e.g. to show the raw state.
+///
+/// Bytes are interpreted first as an address (if they have its length), then as (utf-8) text, and otherwise left as bytes.
+/// Note that this is a guess: e.g. 32 bytes that are actually a hash are decoded as address.
+pub fn decode_state(values: &[TealKeyValue]) -> Result<BTreeMap<String, StateValue>> {
+    let mut key_values = BTreeMap::new();
+    for kv in values {
+        let key_bytes = BASE64.decode(kv.key.as_bytes())?;
+        key_values.insert(String::from_utf8(key_bytes)?, decode_value(&kv.value)?);
+    }
+    Ok(key_values)
+}
+
+fn decode_value(value: &TealValue) -> Result<StateValue> {
+    match &value.value_type {
+        1 => Ok(match value.bytes.clone().try_into() {
+            Ok(array) => StateValue::Addr(Address(array)),
+            Err(bytes) => match String::from_utf8(bytes) {
+                Ok(text) => StateValue::Text(text),
+                Err(e) => StateValue::Bytes(e.into_bytes()),
+            },
+        }),
+        2 => Ok(StateValue::Uint(value.uint)),
+        _ => Err(anyhow!("Unexpected value type: {}", value.value_type)),
+    }
+}
+
 pub fn get_uint_value_or_error(
     state: &ApplicationLocalState,
     key: &AppStateKey<'static>,
@@ -182,3 +275,58 @@ pub fn read_address_from_state(
         ))
     })?))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_state, AppStateKey, StateValue};
+    use algonaut::{
+        core::Address,
+        model::algod::v2::{TealKeyValue, TealValue},
+    };
+    use anyhow::Result;
+
+    #[test]
+    fn test_decode_state() -> Result<()> {
+        let key_values = vec![
+            AppStateKey("Uint").uint_key_value(123),
+            AppStateKey("Addr").bytes_key_value(vec![1; 32]),
+            AppStateKey("Text").bytes_key_value(b"my dao".to_vec()),
+            AppStateKey("Bytes").bytes_key_value(vec![0xff, 0xfe]),
+        ];
+
+        let decoded = decode_state(&key_values)?;
+
+        assert_eq!(Some(&StateValue::Uint(123)), decoded.get("Uint"));
+        assert_eq!(
+            Some(&StateValue::Addr(Address([1; 32]))),
+            decoded.get("Addr")
+        );
+        assert_eq!(
+            Some(&StateValue::Text("my dao".to_owned())),
+            decoded.get("Text")
+        );
+        assert_eq!(
+            Some(&StateValue::Bytes(vec![0xff, 0xfe])),
+            decoded.get("Bytes")
+        );
+        assert_eq!("0xfffe", decoded["Bytes"].to_string());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_state_fails_with_unknown_value_type() -> Result<()> {
+        let key_values = vec![TealKeyValue {
+            key: AppStateKey("Key").to_teal_encoded_str(),
+            value: TealValue {
+                value_type: 3,
+                bytes: vec![],
+                uint: 0,
+            },
+        }];
+
+        assert!(decode_state(&key_values).is_err());
+
+        Ok(())
+    }
+}
@@ -1,30 +1,53 @@
-use super::app_state::{
-    get_uint_value_or_error, local_state, local_state_from_account, AppStateKey,
-    ApplicationGlobalState, ApplicationLocalStateError, ApplicationStateExt,
+use super::{
+    app_state::{
+        decode_state, get_uint_value_or_error, local_state_from_account, AppStateKey,
+        ApplicationGlobalState, ApplicationLocalStateError, ApplicationStateExt,
+    },
+    dividend,
+    state_source::{GlobalStateSource, LocalStateSource, OptedInAccountsSource},
 };
 use crate::{
-    api::version::{bytes_to_versions, Version},
+    api::version::{bytes_to_versions, versions_to_bytes, Version, VersionMismatch, Versions},
+    checked::CheckedMulOther,
     models::{
         dao_app_id::DaoAppId,
-        funds::{FundsAmount, FundsAssetId},
+        funds::{FundsAmount, FundsAssetId, FUNDS_ASSET_DECIMALS},
         hashable::hash,
         nft::Nft,
-        share_amount::ShareAmount,
+        share_amount::{ShareAmount, SharesAssetId},
         shares_percentage::SharesPercentage,
         timestamp::Timestamp,
     },
+    util::{
+        ipfs::IpfsConfig,
+        network_util::with_timeout,
+        retry::{with_retry, RetryPolicy, Retryable},
+    },
 };
 use algonaut::{
     algod::v2::Algod,
     core::Address,
-    model::algod::v2::{Account, ApplicationLocalState, TealKeyValue, TealValue},
+    error::ServiceError,
+    indexer::v2::Indexer,
+    model::{
+        algod::v2::{
+            Account, ApplicationLocalState, ApplicationStateSchema, TealKeyValue, TealValue,
+        },
+        indexer::v2::{QueryTransaction, Transaction},
+    },
 };
 use anyhow::{anyhow, Result};
-use data_encoding::{BASE64, HEXLOWER};
+use data_encoding::BASE64;
+use futures::{stream, StreamExt};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::{
     collections::{BTreeMap, HashMap},
     convert::TryInto,
+    fmt::{self, Display, Formatter},
+    future::Future,
+    ops::RangeInclusive,
+    time::Duration,
 };
 
 const GLOBAL_TOTAL_RECEIVED: AppStateKey = AppStateKey("CentralReceivedTotal");
@@ -62,10 +85,53 @@ const LOCAL_CLAIMED_INIT: AppStateKey = AppStateKey("ClaimedInit");
 const LOCAL_SHARES: AppStateKey = AppStateKey("Shares");
 const LOCAL_SIGNED_PROSPECTUS_URL: AppStateKey = AppStateKey("SignedProspectusUrl");
 const LOCAL_SIGNED_PROSPECTUS_HASH: AppStateKey = AppStateKey("SignedProspectusHash");
+/// Stored as bytes (the TEAL writes the block's timestamp with `itob`), see [Timestamp::to_bytes]
 const LOCAL_SIGNED_PROSPECTUS_TIMESTAMP: AppStateKey = AppStateKey("SignedProspectusTimestamp");
 
 const GLOBAL_SETUP_DATE: AppStateKey = AppStateKey("SetupDate");
 
+const GLOBAL_LOCKUP_END: AppStateKey = AppStateKey("LockupEnd");
+const GLOBAL_VOTING_ASSET_ID: AppStateKey = AppStateKey("VotingAssetId");
+const GLOBAL_MIN_HOLD_PERIOD: AppStateKey = AppStateKey("MinHoldPeriod");
+const GLOBAL_CLAIM_DEADLINE: AppStateKey = AppStateKey("ClaimDeadline");
+
+/// A key that not every DAO has in its state, and the approval contract version since which the state always has it.
+/// From that version on, None is stored as 0 instead of leaving the key out.
+struct OptionalKey {
+    key: AppStateKey<'static>,
+    since: Version,
+}
+
+/// Keys that not every DAO has in its state (they were added after the first contract version, and DAOs with older versions don't have them):
+/// they aren't part of the schema length check, and are read as None when they're not set.
+///
+/// Version 1 (the first deployed version) has none of them, they're set with the contract version that adds them (2).
+const GLOBAL_OPTIONAL_KEYS: &[OptionalKey] = &[
+    OptionalKey {
+        key: GLOBAL_LOCKUP_END,
+        since: Version(2),
+    },
+    OptionalKey {
+        key: GLOBAL_VOTING_ASSET_ID,
+        since: Version(2),
+    },
+    OptionalKey {
+        key: GLOBAL_MIN_HOLD_PERIOD,
+        since: Version(2),
+    },
+    OptionalKey {
+        key: GLOBAL_CLAIM_DEADLINE,
+        since: Version(2),
+    },
+];
+
+/// Whether the state of a DAO with this approval version always has the key (see [GLOBAL_OPTIONAL_KEYS])
+fn has_optional_key(app_approval_version: Version, key: &AppStateKey) -> bool {
+    GLOBAL_OPTIONAL_KEYS
+        .iter()
+        .any(|optional| optional.key == *key && app_approval_version >= optional.since)
+}
+
 // dao name, dao descr, social media, versions, image nft url, prospectus url, prospectus hash, team url
 pub const GLOBAL_SCHEMA_NUM_BYTE_SLICES: u64 = 8;
 // total received, shares asset id, funds asset id, share price, investors part, shares locked, funds target, funds target date,
@@ -76,7 +142,7 @@ pub const LOCAL_SCHEMA_NUM_BYTE_SLICES: u64 = 3; // signed prospectus url, signe
 pub const LOCAL_SCHEMA_NUM_INTS: u64 = 3; // for investors: "shares", "claimed total", "claimed init"
 
 // TODO rename in DaoGlobalState
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CentralAppGlobalState {
     /// Total funds the app has received from customer payments, since it was created
     /// note that it doesn't include capi fees - these are deducated before the amount is added to this
@@ -96,11 +162,12 @@ pub struct CentralAppGlobalState {
     pub app_clear_version: Version,
 
     pub funds_asset_id: FundsAssetId,
-    pub shares_asset_id: u64,
+    pub shares_asset_id: SharesAssetId,
 
     pub project_name: String,
     pub project_desc_url: Option<String>,
     pub share_price: FundsAmount,
+    #[serde(with = "crate::util::shares_percentage_serde")]
     pub investors_share: SharesPercentage,
 
     pub image_nft: Option<Nft>,
@@ -110,6 +177,7 @@ pub struct CentralAppGlobalState {
 
     // fetched from the application, not from state, but here for convenience,
     // (the application is fetched when fetching state)
+    #[serde(with = "crate::util::address_serde")]
     pub owner: Address,
 
     pub locked_shares: ShareAmount,
@@ -124,34 +192,773 @@ pub struct CentralAppGlobalState {
     pub max_invest_amount: ShareAmount,
 
     pub team_url: Option<String>,
+
+    /// Until when the locked shares can't be unlocked. None: shares can be unlocked anytime.
+    pub lockup_end: Option<Timestamp>,
+
+    /// Asset used for governance votes. None: votes use the shares asset.
+    pub voting_asset_id: Option<u64>,
+
+    /// How long the shares have to be held before their dividend can be claimed (stored in seconds).
+    /// None: the dividend of locked shares can be claimed immediately.
+    #[serde(with = "crate::util::optional_duration_secs_serde")]
+    pub min_hold_period: Option<Duration>,
+
+    /// Last moment at which dividend can be claimed: unclaimed dividend expires after it. None: dividend doesn't expire.
+    pub claim_deadline: Option<Timestamp>,
+}
+
+impl CentralAppGlobalState {
+    pub fn lockup_active(&self, now: Timestamp) -> bool {
+        matches!(self.lockup_end, Some(end) if now < end)
+    }
+
+    /// Whether the raise reached the min target (a target of 0 is always reached).
+    /// Until it's reached, investors can get a refund, if the target end date passes.
+    pub fn min_funds_target_reached(&self) -> bool {
+        self.raised.val() >= self.min_funds_target.val()
+    }
+
+    /// Whether shares acquired at `acquired_at` have been held long enough to claim dividend.
+    /// The acquisition time isn't in the investor's local state: it has to be determined by the caller, e.g. from the lock transaction.
+    ///
+    /// This doesn't affect how much dividend the shares are entitled to, which accrues from the moment they're locked
+    /// (see [CentralAppInvestorState::claimable_dividend]): it only determines whether it can be claimed yet.
+    pub fn dividend_eligible(&self, acquired_at: Timestamp, now: Timestamp) -> bool {
+        match self.min_hold_period {
+            Some(period) => now.0 >= acquired_at.0.saturating_add(period.as_secs()),
+            None => true,
+        }
+    }
+
+    pub fn claims_expired(&self, now: Timestamp) -> bool {
+        matches!(self.claim_deadline, Some(deadline) if now > deadline)
+    }
+
+    /// How complete the DAO's profile is, in [0..1], used to rank DAOs in discovery.
+    ///
+    /// Weights: description 0.25, prospectus 0.25, image 0.2, social media 0.15, team 0.15.
+    /// Description and prospectus weigh most, as they're what investors need to evaluate the DAO.
+    /// (Tags aren't part of the DAO's state, so they're not considered.)
+    pub fn completeness_score(&self) -> f64 {
+        [
+            (self.project_desc_url.is_some(), 0.25),
+            (self.prospectus.is_some(), 0.25),
+            (self.image_nft.is_some(), 0.2),
+            (!self.social_media_url.is_empty(), 0.15),
+            (self.team_url.is_some(), 0.15),
+        ]
+        .iter()
+        .filter(|(present, _)| *present)
+        .map(|(_, weight)| weight)
+        .sum()
+    }
+
+    /// Whether governance uses an asset different from the shares
+    pub fn has_separate_voting_token(&self) -> bool {
+        matches!(self.voting_asset_id, Some(id) if id != self.shares_asset_id.0)
+    }
+
+    /// Whether the DAO's (approval) contract version is one the client supports,
+    /// so clients can refuse to interact with contracts upgraded beyond what they understand, instead of misreading the state.
+    pub fn check_compatible(
+        &self,
+        supported: RangeInclusive<Version>,
+    ) -> Result<(), VersionMismatch> {
+        if supported.contains(&self.app_approval_version) {
+            Ok(())
+        } else {
+            Err(VersionMismatch {
+                actual: self.app_approval_version,
+                min_supported: *supported.start(),
+                max_supported: *supported.end(),
+            })
+        }
+    }
+
+    /// Whether an investor can buy `amount` shares in one investment, according to the min and max invest amounts.
+    /// Both bounds are inclusive, a max of 0 means that there's no max.
+    pub fn validate_invest_amount(&self, amount: ShareAmount) -> Result<(), InvestAmountError> {
+        if amount < self.min_invest_amount {
+            return Err(InvestAmountError::BelowMin {
+                min: self.min_invest_amount,
+                got: amount,
+            });
+        }
+        if let Some(max) = self.max_invest() {
+            if amount > max {
+                return Err(InvestAmountError::AboveMax { max, got: amount });
+            }
+        }
+        Ok(())
+    }
+
+    /// How many more shares an investor that holds `already_held` can buy under the max invest amount.
+    /// None: there's no max.
+    pub fn remaining_capacity(&self, already_held: ShareAmount) -> Option<ShareAmount> {
+        self.max_invest()
+            .map(|max| ShareAmount::new(max.val().saturating_sub(already_held.val())))
+    }
+
+    /// The max invest amount, None if there's no max (stored as 0)
+    fn max_invest(&self) -> Option<ShareAmount> {
+        match self.max_invest_amount.val() {
+            0 => None,
+            _ => Some(self.max_invest_amount),
+        }
+    }
+
+    /// State of the raise: the min target is reached as soon as `raised` is >= to it (so a target of 0 is always reached),
+    /// and it failed if the end date passed without reaching it.
+    pub fn funding_status(&self, now: Timestamp) -> FundingStatus {
+        if self.min_funds_target_reached() {
+            FundingStatus::TargetReached
+        } else if now > self.min_funds_target_end_date {
+            FundingStatus::Failed
+        } else {
+            FundingStatus::InProgress {
+                remaining: FundsAmount::new(self.min_funds_target.val() - self.raised.val()),
+            }
+        }
+    }
+
+    /// `raised / min target`, rounded down and clamped to 100% (raising more than the target is allowed).
+    /// 100% if the target is 0.
+    pub fn funding_progress(&self) -> Result<SharesPercentage> {
+        let precision = SharesPercentage::integer_precision();
+        let target = self.min_funds_target.val();
+        if target == 0 {
+            return precision.try_into();
+        }
+        let progress = self.raised.val() as u128 * precision as u128 / target as u128;
+        // clamped to precision, so fits in u64
+        (progress.min(precision as u128) as u64).try_into()
+    }
+}
+
+impl CentralAppGlobalState {
+    /// Encodes the state as key-values, like they're returned by algod: the inverse of [dao_global_state_from_app_state].
+    /// Meant mainly to create state for tests, without a network.
+    ///
+    /// Optional fields that are part of the schema are encoded as empty bytes / 0, like the TEAL initializes them,
+    /// the optional keys (e.g. lockup end) are only included if set.
+    /// The owner isn't part of the global state, so it's not encoded.
+    pub fn to_teal_key_values(&self) -> Result<Vec<TealKeyValue>> {
+        let mut key_values = vec![
+            GLOBAL_TOTAL_RECEIVED.uint_key_value(self.received.val()),
+            GLOBAL_WITHDRAWABLE_AMOUNT.uint_key_value(self.available.val()),
+            GLOBAL_FUNDS_ASSET_ID.uint_key_value(self.funds_asset_id.0),
+            GLOBAL_SHARES_ASSET_ID.uint_key_value(self.shares_asset_id.0),
+            GLOBAL_DAO_NAME.bytes_key_value(self.project_name.as_bytes().to_vec()),
+            GLOBAL_DAO_DESC.bytes_key_value(optional_string_bytes(&self.project_desc_url)),
+            GLOBAL_SHARE_PRICE.uint_key_value(self.share_price.val()),
+            GLOBAL_INVESTORS_SHARE.uint_key_value(self.investors_share.to_u64()?),
+            GLOBAL_IMAGE_ASSET_ID
+                .uint_key_value(self.image_nft.as_ref().map(|nft| nft.asset_id).unwrap_or(0)),
+            GLOBAL_IMAGE_URL.bytes_key_value(
+                self.image_nft
+                    .as_ref()
+                    .map(|nft| nft.url.as_bytes().to_vec())
+                    .unwrap_or_default(),
+            ),
+            GLOBAL_SOCIAL_MEDIA_URL.bytes_key_value(self.social_media_url.as_bytes().to_vec()),
+            GLOBAL_PROSPECTUS_URL.bytes_key_value(
+                self.prospectus
+                    .as_ref()
+                    .map(|p| p.url.as_bytes().to_vec())
+                    .unwrap_or_default(),
+            ),
+            GLOBAL_PROSPECTUS_HASH.bytes_key_value(
+                self.prospectus
+                    .as_ref()
+                    .map(|p| p.hash.as_bytes().to_vec())
+                    .unwrap_or_default(),
+            ),
+            GLOBAL_VERSIONS.bytes_key_value(versions_to_bytes(Versions {
+                app_approval: self.app_approval_version,
+                app_clear: self.app_clear_version,
+            })?),
+            GLOBAL_SHARES_LOCKED.uint_key_value(self.locked_shares.val()),
+            GLOBAL_TARGET.uint_key_value(self.min_funds_target.val()),
+            GLOBAL_TARGET_END_DATE.uint_key_value(self.min_funds_target_end_date.0),
+            GLOBAL_RAISED.uint_key_value(self.raised.val()),
+            GLOBAL_SETUP_DATE.uint_key_value(self.setup_date.0),
+            GLOBAL_MIN_INVEST_AMOUNT.uint_key_value(self.min_invest_amount.val()),
+            GLOBAL_MAX_INVEST_AMOUNT.uint_key_value(self.max_invest_amount.val()),
+            GLOBAL_TEAM_URL.bytes_key_value(optional_string_bytes(&self.team_url)),
+        ];
+
+        let optional_values = [
+            (GLOBAL_LOCKUP_END, self.lockup_end.map(|t| t.0)),
+            (GLOBAL_VOTING_ASSET_ID, self.voting_asset_id),
+            (
+                GLOBAL_MIN_HOLD_PERIOD,
+                self.min_hold_period.map(|d| d.as_secs()),
+            ),
+            (GLOBAL_CLAIM_DEADLINE, self.claim_deadline.map(|t| t.0)),
+        ];
+        for (key, value) in optional_values {
+            if has_optional_key(self.app_approval_version, &key) {
+                key_values.push(key.uint_key_value(value.unwrap_or(0)));
+            } else if let Some(value) = value {
+                key_values.push(key.uint_key_value(value));
+            }
+        }
+
+        Ok(key_values)
+    }
+}
+
+impl Display for CentralAppGlobalState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (_, label, value) in self.display_fields() {
+            writeln!(f, "{label}: {value}")?;
+        }
+        Ok(())
+    }
+}
+
+impl CentralAppGlobalState {
+    /// The fields that differ from `other`, with their formatted values (see [Display]).
+    /// Empty if the states are identical.
+    pub fn diff(&self, other: &CentralAppGlobalState) -> Vec<StateFieldChange> {
+        self.display_fields()
+            .into_iter()
+            .zip(other.display_fields())
+            .filter(|((_, _, old), (_, _, new))| old != new)
+            .map(|((field, _, old), (_, _, new))| StateFieldChange { field, old, new })
+            .collect()
+    }
+
+    /// (field name, label, formatted value) of all the fields
+    fn display_fields(&self) -> Vec<(&'static str, &'static str, String)> {
+        // destructured, so the fields can't be forgotten here when adding them to the struct
+        let CentralAppGlobalState {
+            received,
+            available,
+            app_approval_version,
+            app_clear_version,
+            funds_asset_id,
+            shares_asset_id,
+            project_name,
+            project_desc_url,
+            share_price,
+            investors_share,
+            image_nft,
+            social_media_url,
+            prospectus,
+            owner,
+            locked_shares,
+            min_funds_target,
+            min_funds_target_end_date,
+            raised,
+            setup_date,
+            min_invest_amount,
+            max_invest_amount,
+            team_url,
+            lockup_end,
+            voting_asset_id,
+            min_hold_period,
+            claim_deadline,
+        } = self;
+
+        vec![
+            ("received", "Received", display_funds(*received)),
+            ("available", "Available", display_funds(*available)),
+            (
+                "app_approval_version",
+                "App approval version",
+                app_approval_version.0.to_string(),
+            ),
+            (
+                "app_clear_version",
+                "App clear version",
+                app_clear_version.0.to_string(),
+            ),
+            (
+                "funds_asset_id",
+                "Funds asset id",
+                funds_asset_id.0.to_string(),
+            ),
+            (
+                "shares_asset_id",
+                "Shares asset id",
+                shares_asset_id.0.to_string(),
+            ),
+            ("project_name", "Project name", project_name.clone()),
+            (
+                "project_desc_url",
+                "Description url",
+                display_opt(project_desc_url),
+            ),
+            ("share_price", "Share price", display_funds(*share_price)),
+            (
+                "investors_share",
+                "Investors share",
+                format!(
+                    "{}%",
+                    (investors_share.value() * Decimal::from(100)).normalize()
+                ),
+            ),
+            (
+                "image_nft",
+                "Image nft",
+                display_opt(
+                    &image_nft
+                        .as_ref()
+                        .map(|nft| format!("{} (asset: {})", nft.url, nft.asset_id)),
+                ),
+            ),
+            (
+                "social_media_url",
+                "Social media url",
+                social_media_url.clone(),
+            ),
+            (
+                "prospectus",
+                "Prospectus",
+                display_opt(
+                    &prospectus
+                        .as_ref()
+                        .map(|p| format!("{} (hash: {})", p.url, p.hash)),
+                ),
+            ),
+            ("owner", "Owner", owner.to_string()),
+            ("locked_shares", "Locked shares", locked_shares.to_string()),
+            (
+                "min_funds_target",
+                "Min funds target",
+                display_funds(*min_funds_target),
+            ),
+            (
+                "min_funds_target_end_date",
+                "Min funds target end date",
+                display_timestamp(*min_funds_target_end_date),
+            ),
+            ("raised", "Raised", display_funds(*raised)),
+            ("setup_date", "Setup date", display_timestamp(*setup_date)),
+            (
+                "min_invest_amount",
+                "Min invest amount",
+                min_invest_amount.to_string(),
+            ),
+            (
+                "max_invest_amount",
+                "Max invest amount",
+                max_invest_amount.to_string(),
+            ),
+            ("team_url", "Team url", display_opt(team_url)),
+            (
+                "lockup_end",
+                "Lockup end",
+                display_opt(&lockup_end.map(display_timestamp)),
+            ),
+            (
+                "voting_asset_id",
+                "Voting asset id",
+                display_opt(voting_asset_id),
+            ),
+            (
+                "min_hold_period",
+                "Min hold period",
+                display_opt(&min_hold_period.map(|d| format!("{}s", d.as_secs()))),
+            ),
+            (
+                "claim_deadline",
+                "Claim deadline",
+                display_opt(&claim_deadline.map(display_timestamp)),
+            ),
+        ]
+    }
+}
+
+/// A field that differs between 2 global states
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateFieldChange {
+    pub field: &'static str,
+    pub old: String,
+    pub new: String,
+}
+
+fn display_funds(amount: FundsAmount) -> String {
+    amount.display_with_decimals(FUNDS_ASSET_DECIMALS)
+}
+
+/// RFC3339, or the raw seconds if the timestamp isn't representable as a date
+fn display_timestamp(timestamp: Timestamp) -> String {
+    timestamp
+        .to_date()
+        .map(|date| date.to_rfc3339())
+        .unwrap_or_else(|_| timestamp.0.to_string())
+}
+
+fn display_opt<T: Display>(value: &Option<T>) -> String {
+    value
+        .as_ref()
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| "none".to_owned())
+}
+
+fn optional_string_bytes(str: &Option<String>) -> Vec<u8> {
+    str.as_ref()
+        .map(|s| s.as_bytes().to_vec())
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvestAmountError {
+    BelowMin { min: ShareAmount, got: ShareAmount },
+    AboveMax { max: ShareAmount, got: ShareAmount },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FundingStatus {
+    /// The end date hasn't passed and `remaining` funds are missing to reach the min target
+    InProgress {
+        remaining: FundsAmount,
+    },
+    TargetReached,
+    /// The end date passed without reaching the min target: investors can get a refund
+    Failed,
 }
 
 /// Returns Ok only if called after dao setup (branch_setup_dao), where all the global state is initialized.
-pub async fn dao_global_state(algod: &Algod, app_id: DaoAppId) -> Result<CentralAppGlobalState> {
-    let app = algod.application_information(app_id.0).await?;
-    let gs = ApplicationGlobalState(app.params.global_state);
+/// Before that, it returns [DaoGlobalStateError::NotSetUp].
+pub async fn dao_global_state(
+    algod: &Algod,
+    app_id: DaoAppId,
+) -> Result<CentralAppGlobalState, DaoGlobalStateError> {
+    dao_global_state_from_source(algod, app_id).await
+}
+
+/// [dao_global_state], reading from any state source
+pub async fn dao_global_state_from_source<S: GlobalStateSource>(
+    source: &S,
+    app_id: DaoAppId,
+) -> Result<CentralAppGlobalState, DaoGlobalStateError> {
+    dao_global_states(source, &[app_id], 1)
+        .await?
+        .pop()
+        .map(|(_, res)| res)
+        .ok_or_else(|| {
+            DaoGlobalStateError::Msg(format!("Unexpected: no result for app id: {app_id:?}"))
+        })?
+}
+
+/// Fetches the global state of multiple DAOs, with at most `concurrency` requests at a time.
+///
+/// Each DAO has its own result, so one failing (e.g. not set up yet, or not a DAO) doesn't fail the others.
+/// The results are in the same order as `app_ids`.
+pub async fn dao_global_states<S: GlobalStateSource>(
+    source: &S,
+    app_ids: &[DaoAppId],
+    concurrency: usize,
+) -> Result<Vec<(DaoAppId, Result<CentralAppGlobalState, DaoGlobalStateError>)>> {
+    // buffer_unordered: runs the requests in a FuturesUnordered, so a slow request doesn't block starting the next ones
+    let mut results: Vec<_> = stream::iter(app_ids.iter().enumerate())
+        .map(|(index, app_id)| async move {
+            (
+                index,
+                *app_id,
+                fetch_dao_global_state(source, *app_id).await,
+            )
+        })
+        // buffer_unordered with 0 would never make progress
+        .buffer_unordered(concurrency.max(1))
+        .collect()
+        .await;
+
+    results.sort_by_key(|(index, _, _)| *index);
+
+    Ok(results
+        .into_iter()
+        .map(|(_, app_id, res)| (app_id, res))
+        .collect())
+}
+
+async fn fetch_dao_global_state<S: GlobalStateSource>(
+    source: &S,
+    app_id: DaoAppId,
+) -> Result<CentralAppGlobalState, DaoGlobalStateError> {
+    let (owner, gs) = source.global_state(app_id).await?;
+    dao_global_state_from_app_state(owner, &gs)
+}
 
+/// Parses the DAO's global state, as returned by algod.
+/// The owner isn't in the global state: it's the creator of the application.
+pub fn dao_global_state_from_app_state(
+    owner: Address,
+    gs: &ApplicationGlobalState,
+) -> Result<CentralAppGlobalState, DaoGlobalStateError> {
+    let optional_keys_len = GLOBAL_OPTIONAL_KEYS
+        .iter()
+        .filter(|optional| gs.find(&optional.key).is_some())
+        .count();
     let expected_gs_len = GLOBAL_SCHEMA_NUM_BYTE_SLICES + GLOBAL_SCHEMA_NUM_INTS;
-    if gs.len() != expected_gs_len as usize {
+    if gs.len() - optional_keys_len != expected_gs_len as usize {
         log::debug!("DAO global state:");
         print_state(&gs.0)?;
-        return Err(anyhow!(
-            "Unexpected global state length: {}. Expected: {expected_gs_len}. Was the DAO setup performed already?",
-            gs.len(),
-        ));
+        return Err(DaoGlobalStateError::NotSetUp {
+            actual_len: gs.len(),
+            expected_len: expected_gs_len as usize,
+        });
+    }
+
+    read_dao_global_state_keys(owner, gs)
+}
+
+/// [dao_global_state], retrying transient network errors (see [Retryable]).
+/// Errors in the state itself (e.g. [DaoGlobalStateError::NotSetUp]) fail immediately.
+pub async fn dao_global_state_with_retry(
+    algod: &Algod,
+    app_id: DaoAppId,
+    policy: RetryPolicy,
+) -> Result<CentralAppGlobalState, DaoGlobalStateError> {
+    dao_global_state_with_retry_from_source(algod, app_id, policy).await
+}
+
+/// [dao_global_state_with_retry], reading from any state source
+pub async fn dao_global_state_with_retry_from_source<S: GlobalStateSource>(
+    source: &S,
+    app_id: DaoAppId,
+    policy: RetryPolicy,
+) -> Result<CentralAppGlobalState, DaoGlobalStateError> {
+    with_retry(policy, || dao_global_state_from_source(source, app_id)).await
+}
+
+/// Like [dao_global_state], but without checking the global state length:
+/// reads the keys it knows by name, ignoring unknown ones, and fails only if a required key is missing.
+///
+/// This keeps working while DAOs with older and newer contract versions coexist:
+/// keys added in newer versions are ignored, and optional keys missing in versions older than the one that added them are None.
+/// If the DAO's approval version should have an optional key (see [GLOBAL_OPTIONAL_KEYS]) and it's missing, it fails with [DaoGlobalStateError::MissingKey].
+pub async fn dao_global_state_lenient(
+    algod: &Algod,
+    app_id: DaoAppId,
+) -> Result<CentralAppGlobalState, DaoGlobalStateError> {
+    dao_global_state_lenient_from_source(algod, app_id).await
+}
+
+/// [dao_global_state_lenient], reading from any state source
+pub async fn dao_global_state_lenient_from_source<S: GlobalStateSource>(
+    source: &S,
+    app_id: DaoAppId,
+) -> Result<CentralAppGlobalState, DaoGlobalStateError> {
+    let (owner, gs) = source.global_state(app_id).await?;
+    dao_global_state_lenient_from_app_state(owner, &gs)
+}
+
+/// Parses the DAO's global state without the length check (see [dao_global_state_lenient])
+pub fn dao_global_state_lenient_from_app_state(
+    owner: Address,
+    gs: &ApplicationGlobalState,
+) -> Result<CentralAppGlobalState, DaoGlobalStateError> {
+    // no state at all: not a missing key, the setup wasn't performed yet
+    if gs.is_empty() {
+        return Err(DaoGlobalStateError::NotSetUp {
+            actual_len: 0,
+            expected_len: (GLOBAL_SCHEMA_NUM_BYTE_SLICES + GLOBAL_SCHEMA_NUM_INTS) as usize,
+        });
+    }
+    read_dao_global_state_keys(owner, gs)
+}
+
+/// Transactions per indexer page when replaying the DAO's state history
+const STATE_HISTORY_PAGE_SIZE: u64 = 1000;
+
+/// The DAO's global state as it was at the end of `round`.
+///
+/// The indexer doesn't look up applications at past rounds, so the state is reconstructed
+/// by replaying the global state deltas of the app's transactions, from its creation up to `round`,
+/// and then parsed like the current state (see [dao_global_state]). The owner is the app's creator, i.e. the sender of the creation transaction.
+/// Only the deltas of the (possibly inner) transactions that call or create the app are applied:
+/// the indexer returns the root transaction of a group of inner transactions, which can be a call to a different app.
+///
+/// This depends on the indexer having all the rounds from the app's creation up to `round` archived:
+/// an indexer that's missing the creation (e.g. it started syncing later) errors instead of returning a partial state.
+/// Errors too if `round` predates the app's creation, or if the DAO wasn't set up yet at `round`.
+pub async fn dao_global_state_at_round(
+    indexer: &Indexer,
+    app_id: DaoAppId,
+    round: u64,
+) -> Result<CentralAppGlobalState> {
+    let mut owner: Option<Address> = None;
+    let mut state: BTreeMap<String, TealValue> = BTreeMap::new();
+    let mut next_token: Option<String> = None;
+
+    loop {
+        let page = indexer
+            .transactions(&QueryTransaction {
+                application_id: Some(app_id.0),
+                max_round: Some(round),
+                limit: Some(STATE_HISTORY_PAGE_SIZE),
+                next: next_token.clone(),
+                ..QueryTransaction::default()
+            })
+            .await?;
+
+        // the last page can be empty (the next token is returned as long as the previous page was full)
+        if page.transactions.is_empty() {
+            break;
+        }
+
+        for tx in &page.transactions {
+            replay_app_txn(&mut state, &mut owner, tx, app_id)?;
+            // the transactions are sorted by round, so the first one has to contain the creation
+            if owner.is_none() {
+                return Err(anyhow!(
+                    "The first transaction of app: {app_id:?} isn't its creation: the indexer doesn't have the app's history archived"
+                ));
+            }
+        }
+
+        match page.next_token {
+            Some(token) => next_token = Some(token),
+            None => break,
+        }
+    }
+
+    let owner = owner.ok_or_else(|| {
+        anyhow!(
+            "App: {app_id:?} didn't exist at round: {round}: it was created in a later round, or the indexer doesn't have the round archived"
+        )
+    })?;
+
+    let gs = ApplicationGlobalState(
+        state
+            .into_iter()
+            .map(|(key, value)| TealKeyValue { key, value })
+            .collect(),
+    );
+    Ok(dao_global_state_from_app_state(owner, &gs)?)
+}
+
+/// A global state change, as returned by the indexer (key and bytes base64).
+/// Actions: 1: set bytes, 2: set uint, 3: delete
+struct StateDelta<'a> {
+    key: &'a str,
+    action: u64,
+    bytes: Option<&'a str>,
+    uint: Option<u64>,
+}
+
+/// The parts of an indexer transaction needed to replay an app's global state
+trait AppStateHistoryTx: Sized {
+    fn created_app_id(&self) -> Option<u64>;
+    /// The app called by the transaction, if it's an app call (0 when creating the app)
+    fn called_app_id(&self) -> Option<u64>;
+    fn sender(&self) -> Result<Address>;
+    fn global_state_deltas(&self) -> Vec<StateDelta<'_>>;
+    fn inner_txns(&self) -> &[Self];
+}
+
+impl AppStateHistoryTx for Transaction {
+    fn created_app_id(&self) -> Option<u64> {
+        self.created_application_index
+    }
+
+    fn called_app_id(&self) -> Option<u64> {
+        self.application_transaction
+            .as_ref()
+            .map(|app_call| app_call.application_id)
+    }
+
+    fn sender(&self) -> Result<Address> {
+        self.sender
+            .parse()
+            .map_err(|e| anyhow!("Invalid sender address: {e}"))
+    }
+
+    fn global_state_deltas(&self) -> Vec<StateDelta<'_>> {
+        self.global_state_delta
+            .iter()
+            .flatten()
+            .map(|delta| StateDelta {
+                key: &delta.key,
+                action: delta.value.action,
+                bytes: delta.value.bytes.as_deref(),
+                uint: delta.value.uint,
+            })
+            .collect()
+    }
+
+    fn inner_txns(&self) -> &[Self] {
+        self.inner_txns.as_deref().unwrap_or_default()
+    }
+}
+
+/// Applies the global state deltas of `tx` and its inner transactions (recursively) that create or call `app_id`,
+/// setting `owner` to the sender of the creation.
+fn replay_app_txn<T: AppStateHistoryTx>(
+    state: &mut BTreeMap<String, TealValue>,
+    owner: &mut Option<Address>,
+    tx: &T,
+    app_id: DaoAppId,
+) -> Result<()> {
+    if tx.created_app_id() == Some(app_id.0) {
+        *owner = Some(tx.sender()?);
+    }
+    let target_app_id = tx.created_app_id().or_else(|| tx.called_app_id());
+    if target_app_id == Some(app_id.0) {
+        for delta in tx.global_state_deltas() {
+            apply_state_delta(state, &delta)?;
+        }
+    }
+    for inner in tx.inner_txns() {
+        replay_app_txn(state, owner, inner, app_id)?;
+    }
+    Ok(())
+}
+
+fn apply_state_delta(state: &mut BTreeMap<String, TealValue>, delta: &StateDelta) -> Result<()> {
+    let key = delta.key;
+    match delta.action {
+        1 => {
+            let bytes = BASE64.decode(delta.bytes.unwrap_or_default().as_bytes())?;
+            state.insert(
+                key.to_owned(),
+                TealValue {
+                    value_type: 1,
+                    bytes,
+                    uint: 0,
+                },
+            );
+        }
+        2 => {
+            state.insert(
+                key.to_owned(),
+                TealValue {
+                    value_type: 2,
+                    bytes: vec![],
+                    uint: delta.uint.unwrap_or_default(),
+                },
+            );
+        }
+        3 => {
+            state.remove(key);
+        }
+        action => return Err(anyhow!("Unknown state delta action: {action}, key: {key}")),
     }
+    Ok(())
+}
 
-    let total_received = FundsAmount::new(get_int_or_err(&GLOBAL_TOTAL_RECEIVED, &gs)?);
-    let available = FundsAmount::new(get_int_or_err(&GLOBAL_WITHDRAWABLE_AMOUNT, &gs)?);
+fn read_dao_global_state_keys(
+    owner: Address,
+    gs: &ApplicationGlobalState,
+) -> Result<CentralAppGlobalState, DaoGlobalStateError> {
+    let total_received = FundsAmount::new(get_int_or_err(&GLOBAL_TOTAL_RECEIVED, gs)?);
+    let available = FundsAmount::new(get_int_or_err(&GLOBAL_WITHDRAWABLE_AMOUNT, gs)?);
 
-    let funds_asset_id = FundsAssetId(get_int_or_err(&GLOBAL_FUNDS_ASSET_ID, &gs)?);
-    let shares_asset_id = get_int_or_err(&GLOBAL_SHARES_ASSET_ID, &gs)?;
+    let funds_asset_id = FundsAssetId(get_int_or_err(&GLOBAL_FUNDS_ASSET_ID, gs)?);
+    let shares_asset_id = SharesAssetId(get_int_or_err(&GLOBAL_SHARES_ASSET_ID, gs)?);
 
-    let project_name = String::from_utf8(get_bytes_or_err(&GLOBAL_DAO_NAME, &gs)?)?;
-    let project_desc_url = read_string_none_if_empty(&gs, &GLOBAL_DAO_DESC)?;
+    let project_name = get_string_or_err(&GLOBAL_DAO_NAME, gs)?;
+    let project_desc_url = get_optional_string(&GLOBAL_DAO_DESC, gs)?;
 
-    let share_price = FundsAmount::new(get_int_or_err(&GLOBAL_SHARE_PRICE, &gs)?);
-    let investors_share = get_int_or_err(&GLOBAL_INVESTORS_SHARE, &gs)?.try_into()?;
+    let share_price = FundsAmount::new(get_int_or_err(&GLOBAL_SHARE_PRICE, gs)?);
+    let investors_share = get_int_or_err(&GLOBAL_INVESTORS_SHARE, gs)?
+        .try_into()
+        .map_err(|e| DaoGlobalStateError::invalid_value(&GLOBAL_INVESTORS_SHARE, e))?;
 
     let image_asset_id = gs.find_uint(&GLOBAL_IMAGE_ASSET_ID);
     let image_url = gs.find_bytes(&GLOBAL_IMAGE_URL);
@@ -160,45 +967,50 @@ pub async fn dao_global_state(algod: &Algod, app_id: DaoAppId) -> Result<Central
         (Some(asset_id), Some(url_bytes)) if asset_id == 0 && url_bytes.is_empty() => None,
         (Some(asset_id), Some(url_bytes)) => Some(Nft {
             asset_id,
-            url: String::from_utf8(url_bytes)?,
+            url: String::from_utf8(url_bytes)
+                .map_err(|e| DaoGlobalStateError::invalid_value(&GLOBAL_IMAGE_URL, e))?,
         }),
         (None, None) => None,
-        _ => {
-            return Err(anyhow!(
-                "Invalid state: nft asset id and url must both be set or not set".to_owned()
-            ))
-        }
+        _ => return Err(DaoGlobalStateError::InconsistentNftFields),
     };
 
-    let prospectus_url = read_string_none_if_empty(&gs, &GLOBAL_PROSPECTUS_URL)?;
-    let prospectus_hash = read_string_none_if_empty(&gs, &GLOBAL_PROSPECTUS_HASH)?;
+    let prospectus_url = get_optional_string(&GLOBAL_PROSPECTUS_URL, gs)?;
+    let prospectus_hash = get_optional_string(&GLOBAL_PROSPECTUS_HASH, gs)?;
     let prospectus = match (prospectus_url, prospectus_hash) {
         (Some(url), Some(hash)) => Some(Prospectus { hash, url }),
         (None, None) => None,
-        _ => {
-            return Err(anyhow!(
-                "Invalid state: prospectus hash and url must both be set or not set".to_owned()
-            ))
-        }
+        _ => return Err(DaoGlobalStateError::InconsistentProspectusFields),
     };
 
-    let social_media_url = String::from_utf8(get_bytes_or_err(&GLOBAL_SOCIAL_MEDIA_URL, &gs)?)?;
+    let social_media_url = get_string_or_err(&GLOBAL_SOCIAL_MEDIA_URL, gs)?;
+
+    let versions_bytes = get_bytes_or_err(&GLOBAL_VERSIONS, gs)?;
+    let versions = bytes_to_versions(&versions_bytes)
+        .map_err(|e| DaoGlobalStateError::InvalidVersions(e.to_string()))?;
+    if let Some(optional) = GLOBAL_OPTIONAL_KEYS.iter().find(|optional| {
+        versions.app_approval >= optional.since && gs.find(&optional.key).is_none()
+    }) {
+        return Err(DaoGlobalStateError::MissingKey(optional.key.clone()));
+    }
 
-    let versions_bytes = get_bytes_or_err(&GLOBAL_VERSIONS, &gs)?;
-    let versions = bytes_to_versions(&versions_bytes)?;
+    let shares_locked = ShareAmount::new(get_int_or_err(&GLOBAL_SHARES_LOCKED, gs)?);
 
-    let shares_locked = ShareAmount::new(get_int_or_err(&GLOBAL_SHARES_LOCKED, &gs)?);
+    let min_funds_target = FundsAmount::new(get_int_or_err(&GLOBAL_TARGET, gs)?);
+    let min_funds_target_end_date = Timestamp(get_int_or_err(&GLOBAL_TARGET_END_DATE, gs)?);
+    let raised = FundsAmount::new(get_int_or_err(&GLOBAL_RAISED, gs)?);
 
-    let min_funds_target = FundsAmount::new(get_int_or_err(&GLOBAL_TARGET, &gs)?);
-    let min_funds_target_end_date = Timestamp(get_int_or_err(&GLOBAL_TARGET_END_DATE, &gs)?);
-    let raised = FundsAmount::new(get_int_or_err(&GLOBAL_RAISED, &gs)?);
+    let setup_date = Timestamp(get_int_or_err(&GLOBAL_SETUP_DATE, gs)?);
 
-    let setup_date = Timestamp(get_int_or_err(&GLOBAL_SETUP_DATE, &gs)?);
+    let min_invest_amount = ShareAmount::new(get_int_or_err(&GLOBAL_MIN_INVEST_AMOUNT, gs)?);
+    let max_invest_amount = ShareAmount::new(get_int_or_err(&GLOBAL_MAX_INVEST_AMOUNT, gs)?);
 
-    let min_invest_amount = ShareAmount::new(get_int_or_err(&GLOBAL_MIN_INVEST_AMOUNT, &gs)?);
-    let max_invest_amount = ShareAmount::new(get_int_or_err(&GLOBAL_MAX_INVEST_AMOUNT, &gs)?);
+    let team_url = get_optional_string(&GLOBAL_TEAM_URL, gs)?;
 
-    let team_url = read_string_none_if_empty(&gs, &GLOBAL_TEAM_URL)?;
+    let lockup_end = read_uint_none_if_zero(gs, &GLOBAL_LOCKUP_END).map(Timestamp);
+    let voting_asset_id = read_uint_none_if_zero(gs, &GLOBAL_VOTING_ASSET_ID);
+    let min_hold_period =
+        read_uint_none_if_zero(gs, &GLOBAL_MIN_HOLD_PERIOD).map(Duration::from_secs);
+    let claim_deadline = read_uint_none_if_zero(gs, &GLOBAL_CLAIM_DEADLINE).map(Timestamp);
 
     Ok(CentralAppGlobalState {
         received: total_received,
@@ -214,7 +1026,7 @@ pub async fn dao_global_state(algod: &Algod, app_id: DaoAppId) -> Result<Central
         image_nft,
         social_media_url,
         prospectus,
-        owner: app.params.creator,
+        owner,
         locked_shares: shares_locked,
         min_funds_target,
         min_funds_target_end_date,
@@ -223,6 +1035,10 @@ pub async fn dao_global_state(algod: &Algod, app_id: DaoAppId) -> Result<Central
         min_invest_amount,
         max_invest_amount,
         team_url,
+        lockup_end,
+        voting_asset_id,
+        min_hold_period,
+        claim_deadline,
     })
 }
 
@@ -237,6 +1053,16 @@ where
     })
 }
 
+fn read_uint_none_if_zero<T>(gs: &T, key: &AppStateKey) -> Option<u64>
+where
+    T: ApplicationStateExt,
+{
+    match gs.find_uint(key) {
+        Some(0) | None => None,
+        Some(uint) => Some(uint),
+    }
+}
+
 fn read_bytes_none_if_empty<T>(gs: &T, key: &AppStateKey) -> Option<Vec<u8>>
 where
     T: ApplicationStateExt,
@@ -254,61 +1080,161 @@ where
 }
 
 fn print_state(values: &[TealKeyValue]) -> Result<()> {
-    let mut key_values = BTreeMap::new();
-    for kv in values {
-        let key_bytes = BASE64.decode(kv.key.as_bytes())?;
-        key_values.insert(String::from_utf8(key_bytes)?, value_to_str(&kv.value)?);
+    for (k, v) in decode_state(values)? {
+        log::debug!("{k} => {v}")
+    }
+    Ok(())
+}
+
+fn get_int_or_err(
+    key: &AppStateKey<'static>,
+    gs: &ApplicationGlobalState,
+) -> Result<u64, DaoGlobalStateError> {
+    let value = get_value_or_err(key, gs)?;
+    if value.value_type != 2 {
+        return Err(DaoGlobalStateError::WrongValueType(key.to_owned()));
     }
+    Ok(value.uint)
+}
 
-    // separate step in case we split the fn
-    for (k, v) in key_values {
-        log::debug!("{k} => {v:?}")
+fn get_bytes_or_err(
+    key: &AppStateKey<'static>,
+    gs: &ApplicationGlobalState,
+) -> Result<Vec<u8>, DaoGlobalStateError> {
+    let value = get_value_or_err(key, gs)?;
+    if value.value_type != 1 {
+        return Err(DaoGlobalStateError::WrongValueType(key.to_owned()));
     }
+    Ok(value.bytes)
+}
 
-    Ok(())
+fn get_value_or_err(
+    key: &AppStateKey<'static>,
+    gs: &ApplicationGlobalState,
+) -> Result<TealValue, DaoGlobalStateError> {
+    gs.find(key)
+        .ok_or_else(|| DaoGlobalStateError::MissingKey(key.to_owned()))
+}
+
+fn get_string_or_err(
+    key: &AppStateKey<'static>,
+    gs: &ApplicationGlobalState,
+) -> Result<String, DaoGlobalStateError> {
+    String::from_utf8(get_bytes_or_err(key, gs)?)
+        .map_err(|e| DaoGlobalStateError::invalid_value(key, e))
+}
+
+fn get_optional_string(
+    key: &AppStateKey<'static>,
+    gs: &ApplicationGlobalState,
+) -> Result<Option<String>, DaoGlobalStateError> {
+    read_string_none_if_empty(gs, key).map_err(|e| DaoGlobalStateError::invalid_value(key, e))
+}
+
+#[derive(Debug)]
+pub enum DaoGlobalStateError {
+    /// The global state doesn't have the expected length: the DAO setup wasn't performed yet
+    /// (or the app isn't a DAO). When polling after submitting the setup, this can be retried.
+    NotSetUp {
+        actual_len: usize,
+        expected_len: usize,
+    },
+    MissingKey(AppStateKey<'static>),
+    /// The key is set, but as bytes instead of int or vice versa
+    WrongValueType(AppStateKey<'static>),
+    InvalidVersions(String),
+    /// The value has the right type but can't be converted (e.g. not utf-8, or a percentage out of range)
+    InvalidValue {
+        key: AppStateKey<'static>,
+        details: String,
+    },
+    /// Nft asset id and url must both be set or not set
+    InconsistentNftFields,
+    /// Prospectus hash and url must both be set or not set
+    InconsistentProspectusFields,
+    Algod(ServiceError),
+    Msg(String),
 }
 
-fn value_to_str(value: &TealValue) -> Result<String> {
-    match &value.value_type {
-        1 => Ok(value
-            .bytes
-            .clone()
-            .try_into()
-            // try first to interpret bytes as address
-            .map(|array| Address(array).to_string())
-            // if not address, display as hex
-            .unwrap_or_else(|_| to_hex_str(&value.bytes.clone()))),
-        2 => Ok(value.uint.to_string()),
-        _ => Err(anyhow!(
-            "Unexpected global value type: {}",
-            value.value_type
-        )),
+impl DaoGlobalStateError {
+    fn invalid_value(key: &AppStateKey<'static>, details: impl Display) -> DaoGlobalStateError {
+        DaoGlobalStateError::InvalidValue {
+            key: key.to_owned(),
+            details: details.to_string(),
+        }
     }
 }
 
-fn to_hex_str(bytes: &[u8]) -> String {
-    format!("0x{}", HEXLOWER.encode(bytes))
+impl Display for DaoGlobalStateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            DaoGlobalStateError::NotSetUp {
+                actual_len,
+                expected_len,
+            } => write!(
+                f,
+                "Unexpected global state length: {actual_len}. Expected: {expected_len}. Was the DAO setup performed already?"
+            ),
+            DaoGlobalStateError::MissingKey(key) => write!(f, "Key: {key:?} not set in global state"),
+            DaoGlobalStateError::WrongValueType(key) => {
+                write!(f, "Key: {key:?} has an unexpected value type in global state")
+            }
+            DaoGlobalStateError::InvalidVersions(details) => {
+                write!(f, "Invalid versions in global state: {details}")
+            }
+            DaoGlobalStateError::InvalidValue { key, details } => {
+                write!(f, "Invalid value for key: {key:?} in global state: {details}")
+            }
+            DaoGlobalStateError::InconsistentNftFields => write!(
+                f,
+                "Invalid state: nft asset id and url must both be set or not set"
+            ),
+            DaoGlobalStateError::InconsistentProspectusFields => write!(
+                f,
+                "Invalid state: prospectus hash and url must both be set or not set"
+            ),
+            DaoGlobalStateError::Algod(e) => write!(f, "{e}"),
+            DaoGlobalStateError::Msg(msg) => write!(f, "{msg}"),
+        }
+    }
 }
 
-fn get_int_or_err(key: &AppStateKey, gs: &ApplicationGlobalState) -> Result<u64> {
-    gs.find_uint(key).ok_or_else(|| {
-        anyhow!(
-            "Key: {key:?} (int) not set in global state: {gs:?}, global state len: {}",
-            gs.len()
-        )
-    })
+impl From<ServiceError> for DaoGlobalStateError {
+    fn from(e: ServiceError) -> Self {
+        Self::Algod(e)
+    }
 }
 
-fn get_bytes_or_err(key: &AppStateKey, gs: &ApplicationGlobalState) -> Result<Vec<u8>> {
-    gs.find_bytes(key).ok_or_else(|| {
-        anyhow!(
-            "Key: {key:?} (bytes) not set in global state: {gs:?}, global state len: {}",
-            gs.len()
-        )
-    })
+impl Retryable for DaoGlobalStateError {
+    fn is_retryable(&self) -> bool {
+        match self {
+            DaoGlobalStateError::Algod(e) => e.is_retryable(),
+            // the state (or its absence) doesn't change by fetching it again
+            DaoGlobalStateError::NotSetUp { .. }
+            | DaoGlobalStateError::MissingKey(_)
+            | DaoGlobalStateError::WrongValueType(_)
+            | DaoGlobalStateError::InvalidVersions(_)
+            | DaoGlobalStateError::InvalidValue { .. }
+            | DaoGlobalStateError::InconsistentNftFields
+            | DaoGlobalStateError::InconsistentProspectusFields
+            | DaoGlobalStateError::Msg(_) => false,
+        }
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+impl From<anyhow::Error> for DaoGlobalStateError {
+    fn from(e: anyhow::Error) -> Self {
+        Self::Msg(e.to_string())
+    }
+}
+
+impl From<DaoGlobalStateError> for anyhow::Error {
+    fn from(err: DaoGlobalStateError) -> Self {
+        anyhow!("{}", err)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CentralAppInvestorState {
     // Locked (by definition since it's in the app state - free shares are just assets in the wallet) shares
     pub shares: ShareAmount,
@@ -317,53 +1243,507 @@ pub struct CentralAppInvestorState {
     /// We need this mainly for UX, to subtract it from "claimed", in order to show the user what they actually have claimed.
     /// elaboration: "claimed" is initialized to what the investor would be entitled to receive (based on received global state and held shares),
     /// to prevent double claiming (i.e. we allow to claim dividend only for future income).
-    /// So we need to subtract this initial value from it, to show the investor what they actually claimed.
+    /// So we need to subtract this initial value from it, to show the investor what they actually claimed (see [CentralAppInvestorState::already_claimed]).
     pub claimed_init: FundsAmount,
     pub signed_prospectus: Option<SignedProspectus>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub struct Prospectus {
-    pub hash: String,
-    pub url: String,
-}
+impl CentralAppInvestorState {
+    /// What the investor paid for the locked shares, assuming they were bought at the DAO's share price.
+    /// This is an approximation: shares bought outside of the DAO (e.g. in a secondary market) and locked afterwards
+    /// may have been paid with a different price, which is not visible in the state.
+    pub fn original_investment(&self, global: &CentralAppGlobalState) -> Result<FundsAmount> {
+        global.share_price.mul(self.shares.val())
+    }
 
-impl Prospectus {
-    pub fn new(bytes: &[u8], url: String) -> Prospectus {
-        Prospectus {
-            hash: BASE64.encode(&hash(bytes).0),
-            url,
+    /// What the investor has actually claimed: `claimed` minus the value it was initialized with when locking (see [Self::claimed_init]).
+    pub fn already_claimed(&self) -> FundsAmount {
+        // claimed starts at claimed_init and only increases, so this shouldn't saturate
+        FundsAmount::new(self.claimed.val().saturating_sub(self.claimed_init.val()))
+    }
+
+    /// Dividend the investor can claim now, mirroring the TEAL: what the shares are entitled to
+    /// (`received * investors share * shares / total shares`, see [dividend::entitled_dividend]), minus `claimed`.
+    /// `total_shares` is the shares asset's supply.
+    ///
+    /// Note that `claimed` and not [Self::already_claimed] is subtracted: `claimed` was initialized to `claimed_init`,
+    /// i.e. it includes the dividend for the income received before locking, which the investor isn't allowed to claim.
+    ///
+    /// Errors if the calculation overflows or the state is inconsistent: the investor has more shares than the total,
+    /// or `claimed` exceeds the entitled amount (the entitlement per share only increases, so the investor can't have claimed more).
+    pub fn claimable_dividend(
+        &self,
+        global: &CentralAppGlobalState,
+        total_shares: ShareAmount,
+    ) -> Result<FundsAmount> {
+        let entitled = dividend::entitled_dividend(global, self.shares, total_shares)?;
+        entitled
+            .val()
+            .checked_sub(self.claimed.val())
+            .map(FundsAmount::new)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Invalid state: claimed: {} exceeds entitled dividend: {entitled}",
+                    self.claimed
+                )
+            })
+    }
+
+    /// The investor's part of the locked shares: `shares / locked shares`, rounded down to the percentage's 4 decimals,
+    /// e.g. to show "you own X% of this DAO".
+    ///
+    /// Errors if no shares are locked, or if the investor has more shares than are locked (inconsistent state).
+    pub fn ownership_share(&self, global: &CentralAppGlobalState) -> Result<SharesPercentage> {
+        let (shares, locked) = (self.shares.val(), global.locked_shares.val());
+        if locked == 0 {
+            return Err(anyhow!(
+                "Can't calculate ownership share: no shares are locked"
+            ));
+        }
+        if shares > locked {
+            return Err(anyhow!(
+                "Invalid state: investor has more shares: {shares} than are locked: {locked}"
+            ));
         }
+        // shares <= locked, so the result is <= the precision and fits in u64
+        let share = shares as u128 * SharesPercentage::integer_precision() as u128 / locked as u128;
+        (share as u64).try_into()
     }
-}
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub struct SignedProspectus {
-    pub hash: String,
-    pub url: String,
-    pub timestamp: Timestamp,
-}
+    /// Realized return so far, as a fraction of the investment: `net claimed / original investment`.
+    /// Doesn't include the dividend that can be claimed but hasn't been yet.
+    pub fn yield_to_date(&self, global: &CentralAppGlobalState) -> Result<f64> {
+        let investment = self.original_investment(global)?;
+        if investment.val() == 0 {
+            return Err(anyhow!("Can't calculate yield: investment is 0"));
+        }
+        Ok(self.already_claimed().val() as f64 / investment.val() as f64)
+    }
 
-pub async fn dao_investor_state(
-    algod: &Algod,
-    investor: &Address,
-    app_id: DaoAppId,
-) -> Result<CentralAppInvestorState, ApplicationLocalStateError<'static>> {
-    let local_state = local_state(algod, investor, app_id.0).await?;
-    central_investor_state_from_local_state(&local_state)
-}
+    /// Whether the investor signed the DAO's current prospectus
+    pub fn prospectus_signature(&self, global: &CentralAppGlobalState) -> ProspectusSignature {
+        match (&global.prospectus, &self.signed_prospectus) {
+            (None, _) => ProspectusSignature::NotRequired,
+            (Some(_), None) => ProspectusSignature::Unsigned,
+            (Some(prospectus), Some(signed)) if signed.hash == prospectus.hash => {
+                ProspectusSignature::Signed
+            }
+            (Some(_), Some(_)) => ProspectusSignature::Stale,
+        }
+    }
 
-pub fn central_investor_state_from_acc(
-    account: &Account,
-    app_id: DaoAppId,
-) -> Result<CentralAppInvestorState, ApplicationLocalStateError<'static>> {
+    /// Whether the investor can claim dividend now
+    pub fn can_claim(
+        &self,
+        global: &CentralAppGlobalState,
+        now: Timestamp,
+    ) -> Result<(), CannotClaimReason> {
+        if self.shares.val() == 0 {
+            return Err(CannotClaimReason::NoShares);
+        }
+        if global.claims_expired(now) {
+            return Err(CannotClaimReason::ClaimsExpired);
+        }
+        Ok(())
+    }
+
+    /// Inconsistencies in the investor's state, which indicate a bug (in the contract or when reading the state).
+    /// Empty if the state is healthy.
+    pub fn health(
+        &self,
+        global: &CentralAppGlobalState,
+        now: Timestamp,
+    ) -> Vec<InvestorHealthIssue> {
+        let mut issues = vec![];
+
+        if self.shares > global.locked_shares {
+            issues.push(InvestorHealthIssue::SharesExceedLocked);
+        }
+        if self.claimed.val() < self.claimed_init.val() {
+            issues.push(InvestorHealthIssue::ClaimedLessThanInit);
+        }
+        if let Some(signed) = &self.signed_prospectus {
+            if !signed.timestamp_plausible(global.setup_date, now) {
+                issues.push(InvestorHealthIssue::ImplausibleSignatureTimestamp(
+                    signed.timestamp,
+                ));
+            }
+        }
+
+        issues
+    }
+
+    /// Whether the investor has locked shares that can't be unlocked yet, because the DAO's lockup hasn't ended
+    pub fn is_locked(&self, global: &CentralAppGlobalState, now: Timestamp) -> bool {
+        self.shares.val() > 0 && global.lockup_active(now)
+    }
+
+    /// Whether the investor has locked shares and can unlock them
+    pub fn unlock_available(&self, global: &CentralAppGlobalState, now: Timestamp) -> bool {
+        self.shares.val() > 0 && !global.lockup_active(now)
+    }
+}
+
+impl CentralAppInvestorState {
+    /// Encodes the state as key-values, like they're returned by algod (see [CentralAppGlobalState::to_teal_key_values]).
+    /// A missing signed prospectus is encoded as empty bytes, like the TEAL initializes it.
+    pub fn to_teal_key_values(&self) -> Vec<TealKeyValue> {
+        let signed_prospectus = self.signed_prospectus.as_ref();
+        vec![
+            LOCAL_SHARES.uint_key_value(self.shares.val()),
+            LOCAL_CLAIMED_TOTAL.uint_key_value(self.claimed.val()),
+            LOCAL_CLAIMED_INIT.uint_key_value(self.claimed_init.val()),
+            LOCAL_SIGNED_PROSPECTUS_URL.bytes_key_value(
+                signed_prospectus
+                    .map(|p| p.url.as_bytes().to_vec())
+                    .unwrap_or_default(),
+            ),
+            LOCAL_SIGNED_PROSPECTUS_HASH.bytes_key_value(
+                signed_prospectus
+                    .map(|p| p.hash.as_bytes().to_vec())
+                    .unwrap_or_default(),
+            ),
+            LOCAL_SIGNED_PROSPECTUS_TIMESTAMP.bytes_key_value(
+                signed_prospectus
+                    .map(|p| p.timestamp.to_bytes().to_vec())
+                    .unwrap_or_default(),
+            ),
+        ]
+    }
+}
+
+impl Display for CentralAppInvestorState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Shares: {}", self.shares)?;
+        writeln!(f, "Claimed: {}", display_funds(self.claimed))?;
+        writeln!(f, "Claimed init: {}", display_funds(self.claimed_init))?;
+        writeln!(
+            f,
+            "Already claimed: {}",
+            display_funds(self.already_claimed())
+        )?;
+        match &self.signed_prospectus {
+            Some(signed) => {
+                writeln!(
+                    f,
+                    "Signed prospectus: {} (hash: {})",
+                    signed.url, signed.hash
+                )?;
+                writeln!(
+                    f,
+                    "Signed prospectus timestamp: {}",
+                    display_timestamp(signed.timestamp)
+                )
+            }
+            None => writeln!(f, "Signed prospectus: none"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CannotClaimReason {
+    /// Dividend is only paid to locked shares
+    NoShares,
+    /// The DAO's claim deadline passed
+    ClaimsExpired,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvestorHealthIssue {
+    /// The investor has more shares than the DAO's locked shares
+    SharesExceedLocked,
+    /// Claimed is initialized with claimed init and only increases
+    ClaimedLessThanInit,
+    /// The prospectus signature time is before the DAO's setup or in the future
+    ImplausibleSignatureTimestamp(Timestamp),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProspectusSignature {
+    /// The DAO has no prospectus
+    NotRequired,
+    Signed,
+    Unsigned,
+    /// The investor signed a prospectus that isn't the DAO's current one (it was updated after signing)
+    Stale,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Prospectus {
+    pub hash: String,
+    pub url: String,
+}
+
+impl Prospectus {
+    pub fn new(bytes: &[u8], url: String) -> Prospectus {
+        Prospectus {
+            hash: BASE64.encode(&hash(bytes).0),
+            url,
+        }
+    }
+
+    /// Whether `bytes` is the document this prospectus was created with.
+    /// The document is off-chain and mutable, so this should be checked before trusting it.
+    pub fn verify(&self, bytes: &[u8]) -> bool {
+        hash_matches(&self.hash, bytes)
+    }
+
+    /// Downloads the prospectus document and checks that it matches the hash stored in the DAO's state.
+    /// The document is off-chain and can be changed or removed, without this being visible on-chain.
+    /// If the download doesn't complete within `timeout`, the document is [ProspectusVerifyResult::Unreachable].
+    pub async fn fetch_and_verify(
+        &self,
+        http: &reqwest::Client,
+        ipfs: &IpfsConfig,
+        timeout: Duration,
+    ) -> ProspectusVerifyResult {
+        self.verify_fetched(self.fetch(http, ipfs), timeout).await
+    }
+
+    async fn verify_fetched<F>(&self, fetch: F, timeout: Duration) -> ProspectusVerifyResult
+    where
+        F: Future<Output = Result<Vec<u8>>>,
+    {
+        match with_timeout(fetch, timeout).await.and_then(|res| res) {
+            Ok(bytes) => {
+                if self.verify(&bytes) {
+                    ProspectusVerifyResult::Ok
+                } else {
+                    ProspectusVerifyResult::Mismatch
+                }
+            }
+            Err(e) => ProspectusVerifyResult::Unreachable(e.to_string()),
+        }
+    }
+
+    async fn fetch(&self, http: &reqwest::Client, ipfs: &IpfsConfig) -> Result<Vec<u8>> {
+        let res = http
+            .get(ipfs.resolve(&self.url))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(res.bytes().await?.to_vec())
+    }
+}
+
+/// Compares the (base64) hash in constant time, so the comparison doesn't leak how much of it matches
+fn hash_matches(base64_hash: &str, bytes: &[u8]) -> bool {
+    let expected = base64_hash.as_bytes();
+    let actual = BASE64.encode(&hash(bytes).0);
+    let actual = actual.as_bytes();
+    // the length isn't secret: all the hashes are base64 encoded sha-512/256
+    if expected.len() != actual.len() {
+        return false;
+    }
+    expected
+        .iter()
+        .zip(actual)
+        .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+        == 0
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProspectusVerifyResult {
+    /// The document matches the hash
+    Ok,
+    /// The document was fetched but its hash is different from the one in the state
+    Mismatch,
+    /// The document couldn't be fetched
+    Unreachable(String),
+    /// The DAO has no prospectus, so there's nothing to verify
+    NoProspectus,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedProspectus {
+    pub hash: String,
+    pub url: String,
+    pub timestamp: Timestamp,
+}
+
+/// Tolerance for clock differences between the signing client and the chain
+const SIGNATURE_TIMESTAMP_SKEW: Duration = Duration::from_secs(10 * 60);
+
+impl SignedProspectus {
+    /// Whether `bytes` is the document the investor signed
+    pub fn verify(&self, bytes: &[u8]) -> bool {
+        hash_matches(&self.hash, bytes)
+    }
+
+    /// Whether the signature time is between the DAO's setup and now (with a small skew tolerance).
+    /// A timestamp outside of this range can't be real: it indicates a parsing or storage bug (e.g. millis stored instead of seconds).
+    pub fn timestamp_plausible(&self, dao_setup: Timestamp, now: Timestamp) -> bool {
+        let skew = SIGNATURE_TIMESTAMP_SKEW.as_secs();
+        self.timestamp.0 >= dao_setup.0.saturating_sub(skew)
+            && self.timestamp.0 <= now.0.saturating_add(skew)
+    }
+}
+
+pub async fn dao_investor_state(
+    algod: &Algod,
+    investor: &Address,
+    app_id: DaoAppId,
+) -> Result<CentralAppInvestorState, ApplicationLocalStateError<'static>> {
+    dao_investor_state_from_source(algod, investor, app_id).await
+}
+
+/// [dao_investor_state], retrying transient network errors (see [Retryable])
+pub async fn dao_investor_state_with_retry(
+    algod: &Algod,
+    investor: &Address,
+    app_id: DaoAppId,
+    policy: RetryPolicy,
+) -> Result<CentralAppInvestorState, ApplicationLocalStateError<'static>> {
+    with_retry(policy, || dao_investor_state(algod, investor, app_id)).await
+}
+
+/// [dao_investor_state], reading from any state source
+pub async fn dao_investor_state_from_source<S: LocalStateSource>(
+    source: &S,
+    investor: &Address,
+    app_id: DaoAppId,
+) -> Result<CentralAppInvestorState, ApplicationLocalStateError<'static>> {
+    let local_state = source.local_state(investor, app_id).await?;
+    central_investor_state_from_local_state(&local_state)
+}
+
+pub fn central_investor_state_from_acc(
+    account: &Account,
+    app_id: DaoAppId,
+) -> Result<CentralAppInvestorState, ApplicationLocalStateError<'static>> {
     let local_state = local_state_from_account(account, app_id.0)?;
     central_investor_state_from_local_state(&local_state)
         .map_err(|e| ApplicationLocalStateError::Msg(e.to_string()))
 }
 
+/// Accounts per indexer page when listing investors
+const INVESTORS_PAGE_SIZE: u64 = 100;
+/// Max concurrent algod requests when listing investors
+const INVESTORS_FETCH_CONCURRENCY: usize = 10;
+
+/// The accounts opted in to a DAO (see [dao_investors])
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DaoInvestors {
+    /// The investors' state: None if the account opted in, but its local state hasn't been initialized yet
+    /// (see [try_central_investor_state_from_local_state])
+    pub investors: Vec<(Address, Option<CentralAppInvestorState>)>,
+    /// Opted in accounts whose state couldn't be fetched (after retrying) or parsed
+    pub failed: Vec<(Address, ApplicationLocalStateError<'static>)>,
+}
+
+/// All the accounts opted in to the DAO, including the ones without shares, with their state.
+/// `limit`: max number of accounts to return (investors and failed), None: all of them.
+///
+/// The accounts are listed with the indexer, but their state is read from algod:
+/// the indexer's account model is different, and this way the state is current (the indexer can lag behind).
+/// Accounts that closed out in between are skipped. Fetching an account's state is retried on transient errors (see [RetryPolicy::default]).
+/// An account whose state can't be fetched or parsed doesn't fail the whole list: it's returned in [DaoInvestors::failed], so a cap table can show it's incomplete.
+pub async fn dao_investors(
+    algod: &Algod,
+    indexer: &Indexer,
+    app_id: DaoAppId,
+    limit: Option<usize>,
+) -> Result<DaoInvestors> {
+    dao_investors_from_sources(indexer, algod, app_id, limit).await
+}
+
+/// [dao_investors], listing the accounts and reading their state from any source
+pub async fn dao_investors_from_sources<A, S>(
+    accounts_source: &A,
+    state_source: &S,
+    app_id: DaoAppId,
+    limit: Option<usize>,
+) -> Result<DaoInvestors>
+where
+    A: OptedInAccountsSource,
+    S: LocalStateSource,
+{
+    let mut res = DaoInvestors {
+        investors: vec![],
+        failed: vec![],
+    };
+    let mut next_token: Option<String> = None;
+
+    while !limit_reached(&res, limit) {
+        let (addresses, next) = accounts_source
+            .opted_in_accounts(app_id, INVESTORS_PAGE_SIZE, next_token.clone())
+            .await?;
+
+        // the last page can be empty (the next token is returned as long as the previous page was full)
+        if addresses.is_empty() {
+            break;
+        }
+
+        let states: Vec<_> = stream::iter(addresses)
+            .map(|address| async move {
+                let state = with_retry(RetryPolicy::default(), || {
+                    state_source.local_state(&address, app_id)
+                })
+                .await
+                .and_then(|local_state| try_central_investor_state_from_local_state(&local_state));
+                (address, state)
+            })
+            .buffered(INVESTORS_FETCH_CONCURRENCY)
+            .collect()
+            .await;
+
+        for (address, state) in states {
+            if limit_reached(&res, limit) {
+                return Ok(res);
+            }
+            match state {
+                Ok(state) => res.investors.push((address, state)),
+                // closed out after being listed
+                Err(ApplicationLocalStateError::NotOptedIn) => continue,
+                Err(e) => res.failed.push((address, e)),
+            }
+        }
+
+        match next {
+            Some(token) => next_token = Some(token),
+            None => break,
+        }
+    }
+
+    Ok(res)
+}
+
+fn limit_reached(investors: &DaoInvestors, limit: Option<usize>) -> bool {
+    matches!(limit, Some(limit) if investors.investors.len() + investors.failed.len() >= limit)
+}
+
+/// Like [central_investor_state_from_local_state], but for accounts that may only have opted in to the app:
+/// returns None if the local state hasn't been initialized yet, i.e. the keys aren't set or have the TEAL defaults (0 / empty bytes),
+/// and errors only if the state is malformed (e.g. partially initialized, or a different schema).
+///
+/// Use this to show accounts that may have just opted in (e.g. "opted in, 0 shares" in a wallet view),
+/// and [central_investor_state_from_local_state] where the account is expected to be invested.
+pub fn try_central_investor_state_from_local_state(
+    state: &ApplicationLocalState,
+) -> Result<Option<CentralAppInvestorState>, ApplicationLocalStateError<'static>> {
+    if state.schema.num_byte_slice != LOCAL_SCHEMA_NUM_BYTE_SLICES
+        || state.schema.num_uint != LOCAL_SCHEMA_NUM_INTS
+    {
+        return Err(ApplicationLocalStateError::Msg(format!(
+            "Unexpected investor local state schema: {:?}",
+            state.schema
+        )));
+    }
+
+    let uninitialized = state
+        .key_value
+        .iter()
+        .all(|kv| kv.value.uint == 0 && kv.value.bytes.is_empty());
+    if uninitialized {
+        return Ok(None);
+    }
+
+    central_investor_state_from_local_state(state).map(Some)
+}
+
 /// Expects the user to be invested (as the name indicates) - returns error otherwise.
-fn central_investor_state_from_local_state(
+/// See [try_central_investor_state_from_local_state] for accounts that may only have opted in.
+pub fn central_investor_state_from_local_state(
     state: &ApplicationLocalState,
 ) -> Result<CentralAppInvestorState, ApplicationLocalStateError<'static>> {
     if state.len() != ((LOCAL_SCHEMA_NUM_BYTE_SLICES + LOCAL_SCHEMA_NUM_INTS) as usize) {
@@ -397,12 +1777,11 @@ fn central_investor_state_from_local_state(
         (Some(url), Some(hash), Some(timestamp)) => Some(SignedProspectus {
             hash: hash.to_owned(),
             url: url.to_owned(),
-            timestamp: Timestamp(u64::from_be_bytes(
-                timestamp
-                    .clone()
-                    .try_into()
-                    .map_err(|e: Vec<u8>| ApplicationLocalStateError::Msg(format!("Couldn't convert vec: {:?} to timestamp. Error: {:?}", timestamp, e)))?,
-            )),
+            timestamp: Timestamp::from_bytes(timestamp).map_err(|e| {
+                ApplicationLocalStateError::Msg(format!(
+                    "Invalid signed prospectus timestamp: {e}"
+                ))
+            })?,
         }),
         (None, None, None) => None,
         _ => return Err(ApplicationLocalStateError::Msg(format!("Invalid state in teal: incomplete prospectus {signed_prospectus_url:?}, {signed_prospectus_hash:?}, {signed_prospectus_timestamp:?}"))),
@@ -450,3 +1829,1487 @@ pub fn matches_capi_local_state(app_local_state: &ApplicationLocalState) -> bool
         && state_map.contains_key(&LOCAL_CLAIMED_INIT.to_teal_encoded_str())
         && state_map.contains_key(&LOCAL_SHARES.to_teal_encoded_str())
 }
+
+/// Determines whether global state belongs to a capi app, like [matches_capi_local_state] does for local state:
+/// it's a heuristic (same schema and some distinctive keys), which other apps can imitate intentionally.
+///
+/// The schema has room for the optional keys (see [GLOBAL_OPTIONAL_KEYS]) only in the contract versions that use them,
+/// so the number of ints can be between the base schema and the base schema plus the optional keys.
+pub fn matches_capi_global_state(
+    gs: &ApplicationGlobalState,
+    schema: &ApplicationStateSchema,
+) -> bool {
+    let min_ints = GLOBAL_SCHEMA_NUM_INTS;
+    let max_ints = GLOBAL_SCHEMA_NUM_INTS + GLOBAL_OPTIONAL_KEYS.len() as u64;
+    if !(schema.num_byte_slice == GLOBAL_SCHEMA_NUM_BYTE_SLICES
+        && (min_ints..=max_ints).contains(&schema.num_uint))
+    {
+        return false;
+    }
+
+    [
+        GLOBAL_TOTAL_RECEIVED,
+        GLOBAL_SHARES_ASSET_ID,
+        GLOBAL_FUNDS_ASSET_ID,
+        GLOBAL_VERSIONS,
+    ]
+    .iter()
+    .all(|key| gs.find(key).is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        central_investor_state_from_local_state, dao_global_state_from_app_state,
+        dao_global_state_lenient_from_app_state, dao_global_state_lenient_from_source,
+        dao_global_state_with_retry_from_source, dao_global_states, dao_investors_from_sources,
+        matches_capi_global_state, replay_app_txn, try_central_investor_state_from_local_state,
+        AppStateHistoryTx, CannotClaimReason, CentralAppGlobalState, CentralAppInvestorState,
+        DaoGlobalStateError, FundingStatus, InvestAmountError, InvestorHealthIssue, Prospectus,
+        ProspectusSignature, ProspectusVerifyResult, SignedProspectus, StateDelta,
+        StateFieldChange, GLOBAL_LOCKUP_END, GLOBAL_PROSPECTUS_URL, GLOBAL_SCHEMA_NUM_BYTE_SLICES,
+        GLOBAL_SCHEMA_NUM_INTS, GLOBAL_SHARE_PRICE, GLOBAL_TOTAL_RECEIVED, GLOBAL_VOTING_ASSET_ID,
+        LOCAL_SCHEMA_NUM_BYTE_SLICES, LOCAL_SCHEMA_NUM_INTS, LOCAL_SHARES,
+        LOCAL_SIGNED_PROSPECTUS_TIMESTAMP,
+    };
+    use crate::{
+        api::version::{Version, VersionMismatch},
+        fixtures::{
+            failed_raise, fresh_setup, fully_claimed, funded_and_operating, global_state,
+            investor_state, mid_raise, percentage, MockStateSource,
+        },
+        models::{
+            dao_app_id::DaoAppId, funds::FundsAmount, nft::Nft, share_amount::ShareAmount,
+            timestamp::Timestamp,
+        },
+        state::{
+            app_state::{AppStateKey, ApplicationGlobalState, ApplicationLocalStateError},
+            state_source::{GlobalStateSource, OptedInAccountsSource},
+        },
+        util::retry::{RetryPolicy, Retryable},
+    };
+    use algonaut::{
+        core::Address,
+        error::{RequestError, RequestErrorDetails, ServiceError},
+        model::algod::v2::{ApplicationLocalState, ApplicationStateSchema, TealKeyValue},
+    };
+    use anyhow::Result;
+    use async_trait::async_trait;
+    use data_encoding::BASE64;
+    use futures::{executor::block_on, future};
+    use std::{
+        collections::BTreeMap,
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+        Mutex,
+    };
+
+    #[test]
+    fn test_shares_locked_until_lockup_end() -> Result<()> {
+        let mut global = global_state();
+        global.lockup_end = Some(Timestamp(1000));
+        let investor = investor_state(10);
+
+        assert!(investor.is_locked(&global, Timestamp(999)));
+        assert!(!investor.unlock_available(&global, Timestamp(999)));
+
+        assert!(!investor.is_locked(&global, Timestamp(1000)));
+        assert!(investor.unlock_available(&global, Timestamp(1000)));
+
+        assert!(!investor.is_locked(&global, Timestamp(1001)));
+        assert!(investor.unlock_available(&global, Timestamp(1001)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_shares_unlockable_anytime_without_lockup() -> Result<()> {
+        let mut global = global_state();
+        global.lockup_end = None;
+        let investor = investor_state(10);
+
+        assert!(!investor.is_locked(&global, Timestamp(0)));
+        assert!(investor.unlock_available(&global, Timestamp(0)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_nothing_to_unlock_without_shares() -> Result<()> {
+        let mut global = global_state();
+        global.lockup_end = Some(Timestamp(1000));
+        let investor = investor_state(0);
+
+        assert!(!investor.is_locked(&global, Timestamp(0)));
+        assert!(!investor.unlock_available(&global, Timestamp(2000)));
+
+        Ok(())
+    }
+
+    /// Encodes a DAO (of a version without the optional keys) and parses it, with `key` set to `value`, or missing if None
+    fn parse_with_optional_key(
+        key: &AppStateKey,
+        value: Option<u64>,
+    ) -> Result<CentralAppGlobalState, DaoGlobalStateError> {
+        let global = global_state();
+        let mut key_values = global.to_teal_key_values()?;
+        if let Some(value) = value {
+            key_values.push(key.uint_key_value(value));
+        }
+        dao_global_state_from_app_state(global.owner, &ApplicationGlobalState(key_values))
+    }
+
+    #[test]
+    fn test_lockup_end_read_as_none_if_absent_or_zero() -> Result<()> {
+        assert_eq!(
+            None,
+            parse_with_optional_key(&GLOBAL_LOCKUP_END, None)?.lockup_end
+        );
+        assert_eq!(
+            None,
+            parse_with_optional_key(&GLOBAL_LOCKUP_END, Some(0))?.lockup_end
+        );
+        assert_eq!(
+            Some(Timestamp(1000)),
+            parse_with_optional_key(&GLOBAL_LOCKUP_END, Some(1000))?.lockup_end
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_separate_voting_token_if_voting_asset_set() -> Result<()> {
+        let global = parse_with_optional_key(&GLOBAL_VOTING_ASSET_ID, Some(789))?;
+
+        assert_eq!(Some(789), global.voting_asset_id);
+        assert!(global.has_separate_voting_token());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_separate_voting_token_if_voting_asset_zero_or_absent() -> Result<()> {
+        for value in [Some(0), None] {
+            let global = parse_with_optional_key(&GLOBAL_VOTING_ASSET_ID, value)?;
+            assert_eq!(None, global.voting_asset_id);
+            assert!(!global.has_separate_voting_token());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_separate_voting_token_if_voting_asset_is_shares_asset() -> Result<()> {
+        let mut global = global_state();
+        global.voting_asset_id = Some(global.shares_asset_id.0);
+
+        assert!(!global.has_separate_voting_token());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_yield_to_date_is_0_if_never_claimed() -> Result<()> {
+        let mut investor = investor_state(100);
+        investor.claimed_init = FundsAmount::new(50);
+        investor.claimed = FundsAmount::new(50);
+
+        assert_eq!(0.0, investor.yield_to_date(&global_state())?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_yield_to_date_with_partial_claim() -> Result<()> {
+        let mut global = global_state();
+        global.share_price = FundsAmount::new(10);
+        let mut investor = investor_state(100);
+        investor.claimed_init = FundsAmount::new(50);
+        investor.claimed = FundsAmount::new(300);
+
+        // (300 - 50) / (100 * 10)
+        assert_eq!(0.25, investor.yield_to_date(&global)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_yield_to_date_errors_without_investment() -> Result<()> {
+        assert!(investor_state(0).yield_to_date(&global_state()).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dividend_eligible_after_min_hold_period() -> Result<()> {
+        let mut global = global_state();
+        global.min_hold_period = Some(Duration::from_secs(100));
+
+        assert!(!global.dividend_eligible(Timestamp(1000), Timestamp(1099)));
+        assert!(global.dividend_eligible(Timestamp(1000), Timestamp(1100)));
+        assert!(global.dividend_eligible(Timestamp(1000), Timestamp(2000)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dividend_eligible_immediately_without_min_hold_period() -> Result<()> {
+        let mut global = global_state();
+        global.min_hold_period = None;
+
+        assert!(global.dividend_eligible(Timestamp(1000), Timestamp(1000)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prospectus_signature() -> Result<()> {
+        let mut global = global_state();
+        let mut investor = investor_state(10);
+        assert_eq!(
+            ProspectusSignature::NotRequired,
+            investor.prospectus_signature(&global)
+        );
+
+        let prospectus = Prospectus::new(b"current", "https://example.com/p".to_owned());
+        global.prospectus = Some(prospectus.clone());
+        assert_eq!(
+            ProspectusSignature::Unsigned,
+            investor.prospectus_signature(&global)
+        );
+
+        investor.signed_prospectus = Some(SignedProspectus {
+            hash: prospectus.hash,
+            url: prospectus.url,
+            timestamp: Timestamp(1000),
+        });
+        assert_eq!(
+            ProspectusSignature::Signed,
+            investor.prospectus_signature(&global)
+        );
+
+        global.prospectus = Some(Prospectus::new(
+            b"updated",
+            "https://example.com/p".to_owned(),
+        ));
+        assert_eq!(
+            ProspectusSignature::Stale,
+            investor.prospectus_signature(&global)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_claims_expire_after_deadline() -> Result<()> {
+        let mut global = global_state();
+        global.claim_deadline = Some(Timestamp(1000));
+        let investor = investor_state(10);
+
+        assert!(!global.claims_expired(Timestamp(1000)));
+        assert_eq!(Ok(()), investor.can_claim(&global, Timestamp(1000)));
+
+        assert!(global.claims_expired(Timestamp(1001)));
+        assert_eq!(
+            Err(CannotClaimReason::ClaimsExpired),
+            investor.can_claim(&global, Timestamp(1001))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_claims_dont_expire_without_deadline() -> Result<()> {
+        let mut global = global_state();
+        global.claim_deadline = None;
+
+        assert!(!global.claims_expired(Timestamp(u64::MAX)));
+        assert_eq!(
+            Ok(()),
+            investor_state(10).can_claim(&global, Timestamp(u64::MAX))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cant_claim_without_shares() -> Result<()> {
+        assert_eq!(
+            Err(CannotClaimReason::NoShares),
+            investor_state(0).can_claim(&global_state(), Timestamp(0))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_completeness_score_of_empty_dao() -> Result<()> {
+        let mut global = global_state();
+        global.social_media_url = "".to_owned();
+
+        assert_eq!(0.0, global.completeness_score());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_completeness_score_of_fully_populated_dao() -> Result<()> {
+        let mut global = global_state();
+        global.project_desc_url = Some("https://example.com/desc".to_owned());
+        global.prospectus = Some(Prospectus::new(b"doc", "https://example.com/p".to_owned()));
+        global.image_nft = Some(Nft {
+            url: "https://example.com/image".to_owned(),
+            asset_id: 123,
+        });
+        global.social_media_url = "https://twitter.com/dao".to_owned();
+        global.team_url = Some("https://example.com/team".to_owned());
+
+        assert!((1.0 - global.completeness_score()).abs() < 1e-9);
+
+        global.team_url = None;
+        assert!((0.85 - global.completeness_score()).abs() < 1e-9);
+
+        Ok(())
+    }
+
+    fn signed_prospectus(timestamp: u64) -> SignedProspectus {
+        SignedProspectus {
+            hash: "hash".to_owned(),
+            url: "https://example.com/p".to_owned(),
+            timestamp: Timestamp(timestamp),
+        }
+    }
+
+    #[test]
+    fn test_signature_timestamp_before_setup_not_plausible() -> Result<()> {
+        let setup = Timestamp(1_650_000_000);
+        let now = Timestamp(1_660_000_000);
+
+        // beyond skew
+        assert!(!signed_prospectus(setup.0 - 3600).timestamp_plausible(setup, now));
+        // within skew
+        assert!(signed_prospectus(setup.0 - 60).timestamp_plausible(setup, now));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_signature_timestamp_in_future_not_plausible() -> Result<()> {
+        let setup = Timestamp(1_650_000_000);
+        let now = Timestamp(1_660_000_000);
+
+        assert!(!signed_prospectus(now.0 + 3600).timestamp_plausible(setup, now));
+        // millis instead of seconds
+        assert!(!signed_prospectus(now.0 * 1000).timestamp_plausible(setup, now));
+        // within skew
+        assert!(signed_prospectus(now.0 + 60).timestamp_plausible(setup, now));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_signature_timestamp_plausible() -> Result<()> {
+        let setup = Timestamp(1_650_000_000);
+        let now = Timestamp(1_660_000_000);
+
+        assert!(signed_prospectus(setup.0).timestamp_plausible(setup, now));
+        assert!(signed_prospectus(1_655_000_000).timestamp_plausible(setup, now));
+        assert!(signed_prospectus(now.0).timestamp_plausible(setup, now));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_health_reports_implausible_signature_timestamp() -> Result<()> {
+        let mut global = global_state();
+        global.setup_date = Timestamp(1_650_000_000);
+        global.locked_shares = ShareAmount::new(10);
+        let mut investor = investor_state(10);
+
+        investor.signed_prospectus = Some(signed_prospectus(1_655_000_000));
+        assert!(investor
+            .health(&global, Timestamp(1_660_000_000))
+            .is_empty());
+
+        investor.signed_prospectus = Some(signed_prospectus(1_655_000_000_000));
+        assert_eq!(
+            vec![InvestorHealthIssue::ImplausibleSignatureTimestamp(
+                Timestamp(1_655_000_000_000)
+            )],
+            investor.health(&global, Timestamp(1_660_000_000))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_claimable_dividend_subtracts_claimed_including_init() -> Result<()> {
+        let mut global = global_state();
+        global.received = FundsAmount::new(1000);
+        global.locked_shares = ShareAmount::new(50);
+        let mut investor = investor_state(50);
+        investor.claimed_init = FundsAmount::new(100);
+        investor.claimed = FundsAmount::new(150);
+
+        // entitled: 1000 * 0.4 * 50 / 100 = 200
+        assert_eq!(
+            FundsAmount::new(50),
+            investor.claimable_dividend(&global, ShareAmount::new(100))?
+        );
+        assert_eq!(FundsAmount::new(50), investor.already_claimed());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_claimable_dividend_errors_if_claimed_exceeds_entitled() -> Result<()> {
+        let mut global = global_state();
+        global.received = FundsAmount::new(1000);
+        global.locked_shares = ShareAmount::new(100);
+        let mut investor = investor_state(50);
+        investor.claimed = FundsAmount::new(300);
+
+        // entitled: 1000 * 0.4 * 50 / 100 = 200
+        assert!(investor
+            .claimable_dividend(&global, ShareAmount::new(100))
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_claimable_dividend_errors_if_shares_exceed_total() -> Result<()> {
+        let global = global_state();
+
+        assert!(investor_state(20)
+            .claimable_dividend(&global, ShareAmount::new(10))
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_state_json_round_trip() -> Result<()> {
+        let mut snapshot = funded_and_operating();
+        snapshot.global.min_hold_period = Some(Duration::from_secs(3600));
+
+        let global_json = serde_json::to_string(&snapshot.global)?;
+        let global: CentralAppGlobalState = serde_json::from_str(&global_json)?;
+        assert_eq!(snapshot.global, global);
+
+        let value: serde_json::Value = serde_json::from_str(&global_json)?;
+        // the address is serialized as its base32 string
+        assert_eq!(
+            serde_json::json!(snapshot.global.owner.to_string()),
+            value["owner"]
+        );
+        // the percentage as its integer representation (40%), the duration in seconds
+        assert_eq!(serde_json::json!(4000), value["investors_share"]);
+        assert_eq!(serde_json::json!(3600), value["min_hold_period"]);
+
+        snapshot.global.min_hold_period = None;
+        let value = serde_json::to_value(&snapshot.global)?;
+        assert_eq!(serde_json::Value::Null, value["min_hold_period"]);
+
+        for (_, investor) in snapshot.investors {
+            let json = serde_json::to_string(&investor)?;
+            let deserialized: CentralAppInvestorState = serde_json::from_str(&json)?;
+            assert_eq!(investor, deserialized);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_funding_status() -> Result<()> {
+        let mut global = global_state();
+        global.min_funds_target = FundsAmount::new(1000);
+        global.min_funds_target_end_date = Timestamp(100);
+        global.raised = FundsAmount::new(400);
+
+        assert_eq!(
+            FundingStatus::InProgress {
+                remaining: FundsAmount::new(600)
+            },
+            global.funding_status(Timestamp(100))
+        );
+        assert_eq!(FundingStatus::Failed, global.funding_status(Timestamp(101)));
+
+        global.raised = FundsAmount::new(1000);
+        assert_eq!(
+            FundingStatus::TargetReached,
+            global.funding_status(Timestamp(101))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_funding_status_with_0_target_is_reached() -> Result<()> {
+        let mut global = global_state();
+        global.min_funds_target_end_date = Timestamp(100);
+
+        assert_eq!(
+            FundingStatus::TargetReached,
+            global.funding_status(Timestamp(50))
+        );
+        assert_eq!(
+            FundingStatus::TargetReached,
+            global.funding_status(Timestamp(200))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_funding_progress() -> Result<()> {
+        let mut global = global_state();
+        assert_eq!(percentage("1"), global.funding_progress()?);
+
+        global.min_funds_target = FundsAmount::new(3000);
+        global.raised = FundsAmount::new(1000);
+        // rounded down
+        assert_eq!(percentage("0.3333"), global.funding_progress()?);
+
+        global.raised = FundsAmount::new(4000);
+        assert_eq!(percentage("1"), global.funding_progress()?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prospectus_verify() -> Result<()> {
+        let document = b"prospectus document".to_vec();
+        let prospectus = Prospectus::new(&document, "https://example.com".to_owned());
+        let signed = SignedProspectus {
+            hash: prospectus.hash.clone(),
+            url: prospectus.url.clone(),
+            timestamp: Timestamp(0),
+        };
+
+        let mut altered = document.clone();
+        altered[0] ^= 1;
+
+        assert!(prospectus.verify(&document));
+        assert!(signed.verify(&document));
+        assert!(!prospectus.verify(&altered));
+        assert!(!signed.verify(&altered));
+        assert!(!prospectus.verify(&[]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prospectus_of_empty_document_verifies_empty_document() -> Result<()> {
+        let prospectus = Prospectus::new(&[], "https://example.com".to_owned());
+
+        assert!(prospectus.verify(&[]));
+        assert!(!prospectus.verify(b"a"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_teal_key_values_round_trip() -> Result<()> {
+        let mut with_all_optionals = funded_and_operating().global;
+        with_all_optionals.image_nft = Some(Nft {
+            asset_id: 789,
+            url: "https://example.com/image.png".to_owned(),
+        });
+        with_all_optionals.team_url = Some("https://example.com/team".to_owned());
+        with_all_optionals.lockup_end = Some(Timestamp(1_700_000_000));
+        with_all_optionals.voting_asset_id = Some(999);
+        with_all_optionals.min_hold_period = Some(Duration::from_secs(3600));
+        with_all_optionals.claim_deadline = Some(Timestamp(1_800_000_000));
+
+        for global in [
+            global_state(),
+            fresh_setup().global,
+            mid_raise().global,
+            funded_and_operating().global,
+            failed_raise().global,
+            fully_claimed().global,
+            with_all_optionals,
+        ] {
+            let gs = ApplicationGlobalState(global.to_teal_key_values()?);
+            let parsed = dao_global_state_from_app_state(global.owner, &gs)?;
+            assert_eq!(global, parsed);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_display_global_state() -> Result<()> {
+        let mut global = funded_and_operating().global;
+        global.lockup_end = Some(Timestamp(1_700_000_000));
+
+        let displayed = global.to_string();
+
+        assert!(displayed.contains("Project name: Coffee shop\n"));
+        assert!(displayed.contains("Received: 5000.000000\n"));
+        assert!(displayed.contains("Investors share: 40%\n"));
+        assert!(displayed.contains("Setup date: 2022-04-15T05:20:00+00:00\n"));
+        assert!(displayed.contains("Lockup end: 2023-11-14T22:13:20+00:00\n"));
+        assert!(displayed.contains("Team url: none\n"));
+        assert!(displayed.contains("Claim deadline: none\n"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_display_investor_state() -> Result<()> {
+        let (_, investor) = funded_and_operating().investors.remove(0);
+
+        let displayed = investor.to_string();
+
+        assert!(displayed.contains("Shares: 6000\n"));
+        assert!(displayed.contains("Claimed: 250.000000\n"));
+        assert!(displayed.contains("Already claimed: 250.000000\n"));
+        assert!(displayed.contains("Signed prospectus timestamp: 2022-04-16T05:20:00+00:00\n"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parsing_empty_global_state_is_not_set_up() -> Result<()> {
+        let res =
+            dao_global_state_from_app_state(Address([0; 32]), &ApplicationGlobalState(vec![]));
+
+        assert!(matches!(
+            res,
+            Err(DaoGlobalStateError::NotSetUp {
+                actual_len: 0,
+                expected_len: 22
+            })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parsing_global_state_with_inconsistent_prospectus_fails() -> Result<()> {
+        let global = global_state();
+        let mut key_values = global.to_teal_key_values()?;
+        for kv in &mut key_values {
+            if kv.key == GLOBAL_PROSPECTUS_URL.to_teal_encoded_str() {
+                kv.value.bytes = b"https://example.com".to_vec();
+            }
+        }
+
+        let res =
+            dao_global_state_from_app_state(global.owner, &ApplicationGlobalState(key_values));
+
+        assert!(matches!(
+            res,
+            Err(DaoGlobalStateError::InconsistentProspectusFields)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parsing_global_state_with_wrong_value_type_fails() -> Result<()> {
+        let global = global_state();
+        let mut key_values = global.to_teal_key_values()?;
+        for kv in &mut key_values {
+            if kv.key == GLOBAL_SHARE_PRICE.to_teal_encoded_str() {
+                kv.value.value_type = 1;
+            }
+        }
+
+        let res =
+            dao_global_state_from_app_state(global.owner, &ApplicationGlobalState(key_values));
+
+        assert!(matches!(
+            res,
+            Err(DaoGlobalStateError::WrongValueType(key)) if key == GLOBAL_SHARE_PRICE
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_compatible() -> Result<()> {
+        let mut global = global_state();
+        global.app_approval_version = Version(3);
+
+        assert_eq!(Ok(()), global.check_compatible(Version(1)..=Version(3)));
+        assert_eq!(Ok(()), global.check_compatible(Version(3)..=Version(3)));
+        assert_eq!(
+            Err(VersionMismatch {
+                actual: Version(3),
+                min_supported: Version(1),
+                max_supported: Version(2)
+            }),
+            global.check_compatible(Version(1)..=Version(2))
+        );
+        assert!(global.check_compatible(Version(4)..=Version(5)).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_invest_amount() -> Result<()> {
+        let mut global = global_state();
+        global.min_invest_amount = ShareAmount::new(10);
+        global.max_invest_amount = ShareAmount::new(100);
+
+        assert_eq!(Ok(()), global.validate_invest_amount(ShareAmount::new(10)));
+        assert_eq!(Ok(()), global.validate_invest_amount(ShareAmount::new(100)));
+        assert_eq!(
+            Err(InvestAmountError::BelowMin {
+                min: ShareAmount::new(10),
+                got: ShareAmount::new(9)
+            }),
+            global.validate_invest_amount(ShareAmount::new(9))
+        );
+        assert_eq!(
+            Err(InvestAmountError::AboveMax {
+                max: ShareAmount::new(100),
+                got: ShareAmount::new(101)
+            }),
+            global.validate_invest_amount(ShareAmount::new(101))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_max_invest_amount_0_means_no_max() -> Result<()> {
+        let mut global = global_state();
+        global.max_invest_amount = ShareAmount::new(0);
+
+        assert_eq!(
+            Ok(()),
+            global.validate_invest_amount(ShareAmount::new(u64::MAX))
+        );
+        assert_eq!(None, global.remaining_capacity(ShareAmount::new(1000)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_remaining_capacity() -> Result<()> {
+        let mut global = global_state();
+        global.max_invest_amount = ShareAmount::new(100);
+
+        assert_eq!(
+            Some(ShareAmount::new(70)),
+            global.remaining_capacity(ShareAmount::new(30))
+        );
+        assert_eq!(
+            Some(ShareAmount::new(0)),
+            global.remaining_capacity(ShareAmount::new(150))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff_identical_states_is_empty() -> Result<()> {
+        let global = funded_and_operating().global;
+
+        assert!(global.diff(&global.clone()).is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff_reports_changed_fields() -> Result<()> {
+        let old = funded_and_operating().global;
+        let mut new = old.clone();
+        new.received = FundsAmount::new(6_000_000_000);
+        new.available = FundsAmount::new(6_000_000_000);
+        new.prospectus = None;
+        new.team_url = Some("https://example.com/team".to_owned());
+
+        assert_eq!(
+            vec![
+                StateFieldChange {
+                    field: "received",
+                    old: "5000.000000".to_owned(),
+                    new: "6000.000000".to_owned()
+                },
+                StateFieldChange {
+                    field: "available",
+                    old: "5000.000000".to_owned(),
+                    new: "6000.000000".to_owned()
+                },
+                StateFieldChange {
+                    field: "prospectus",
+                    old: format!(
+                        "{} (hash: {})",
+                        old.prospectus.as_ref().unwrap().url,
+                        old.prospectus.as_ref().unwrap().hash
+                    ),
+                    new: "none".to_owned()
+                },
+                StateFieldChange {
+                    field: "team_url",
+                    old: "none".to_owned(),
+                    new: "https://example.com/team".to_owned()
+                },
+            ],
+            old.diff(&new)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_matches_capi_global_state() -> Result<()> {
+        let gs = ApplicationGlobalState(global_state().to_teal_key_values()?);
+        let schema = ApplicationStateSchema {
+            num_byte_slice: GLOBAL_SCHEMA_NUM_BYTE_SLICES,
+            num_uint: GLOBAL_SCHEMA_NUM_INTS,
+        };
+
+        assert!(matches_capi_global_state(&gs, &schema));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_does_not_match_capi_global_state_with_shorter_schema() -> Result<()> {
+        let gs = ApplicationGlobalState(global_state().to_teal_key_values()?);
+        let schema = ApplicationStateSchema {
+            num_byte_slice: GLOBAL_SCHEMA_NUM_BYTE_SLICES,
+            num_uint: GLOBAL_SCHEMA_NUM_INTS - 1,
+        };
+
+        assert!(!matches_capi_global_state(&gs, &schema));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_does_not_match_capi_global_state_without_distinctive_key() -> Result<()> {
+        let mut key_values = global_state().to_teal_key_values()?;
+        key_values.retain(|kv| kv.key != GLOBAL_TOTAL_RECEIVED.to_teal_encoded_str());
+        let schema = ApplicationStateSchema {
+            num_byte_slice: GLOBAL_SCHEMA_NUM_BYTE_SLICES,
+            num_uint: GLOBAL_SCHEMA_NUM_INTS,
+        };
+
+        assert!(!matches_capi_global_state(
+            &ApplicationGlobalState(key_values),
+            &schema
+        ));
+
+        Ok(())
+    }
+
+    fn investor_local_state(key_value: Vec<TealKeyValue>) -> ApplicationLocalState {
+        ApplicationLocalState {
+            id: 123,
+            key_value,
+            schema: ApplicationStateSchema {
+                num_byte_slice: LOCAL_SCHEMA_NUM_BYTE_SLICES,
+                num_uint: LOCAL_SCHEMA_NUM_INTS,
+            },
+        }
+    }
+
+    #[test]
+    fn test_try_investor_state_is_none_if_only_opted_in() -> Result<()> {
+        assert_eq!(
+            None,
+            try_central_investor_state_from_local_state(&investor_local_state(vec![]))?
+        );
+        // initialized with the TEAL defaults
+        assert_eq!(
+            None,
+            try_central_investor_state_from_local_state(&investor_local_state(
+                investor_state(0).to_teal_key_values()
+            ))?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_try_investor_state_with_invested_account() -> Result<()> {
+        let investor = funded_and_operating().investors.remove(0).1;
+
+        assert_eq!(
+            Some(investor.clone()),
+            try_central_investor_state_from_local_state(&investor_local_state(
+                investor.to_teal_key_values()
+            ))?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_try_investor_state_fails_if_malformed() -> Result<()> {
+        // partially initialized
+        let partial = investor_local_state(vec![LOCAL_SHARES.uint_key_value(10)]);
+        assert!(try_central_investor_state_from_local_state(&partial).is_err());
+
+        let mut other_schema = investor_local_state(vec![]);
+        other_schema.schema.num_uint = 1;
+        assert!(try_central_investor_state_from_local_state(&other_schema).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_signed_prospectus_timestamp_round_trip() -> Result<()> {
+        let mut investor = investor_state(10);
+        investor.signed_prospectus = Some(SignedProspectus {
+            hash: "hash".to_owned(),
+            url: "https://example.com".to_owned(),
+            timestamp: Timestamp(1_650_086_400),
+        });
+
+        let parsed = central_investor_state_from_local_state(&investor_local_state(
+            investor.to_teal_key_values(),
+        ))?;
+
+        assert_eq!(investor, parsed);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_signed_prospectus_timestamp_with_wrong_length_fails() -> Result<()> {
+        let mut investor = investor_state(10);
+        investor.signed_prospectus = Some(SignedProspectus {
+            hash: "hash".to_owned(),
+            url: "https://example.com".to_owned(),
+            timestamp: Timestamp(1_650_086_400),
+        });
+        let mut key_values = investor.to_teal_key_values();
+        for kv in &mut key_values {
+            if kv.key == LOCAL_SIGNED_PROSPECTUS_TIMESTAMP.to_teal_encoded_str() {
+                kv.value.bytes = vec![1, 2, 3, 4];
+            }
+        }
+
+        let res = central_investor_state_from_local_state(&investor_local_state(key_values));
+
+        assert!(matches!(res, Err(e) if e.to_string().contains("Expected 8 bytes")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lenient_parsing_ignores_unknown_keys() -> Result<()> {
+        let global = funded_and_operating().global;
+        let mut key_values = global.to_teal_key_values()?;
+        key_values.push(AppStateKey("KeyOfNewerVersion").uint_key_value(1));
+        let gs = ApplicationGlobalState(key_values);
+
+        assert!(matches!(
+            dao_global_state_from_app_state(global.owner, &gs),
+            Err(DaoGlobalStateError::NotSetUp { .. })
+        ));
+        assert_eq!(
+            global,
+            dao_global_state_lenient_from_app_state(global.owner, &gs)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lenient_parsing_fails_without_required_key() -> Result<()> {
+        let global = funded_and_operating().global;
+        let mut key_values = global.to_teal_key_values()?;
+        key_values.retain(|kv| kv.key != GLOBAL_SHARE_PRICE.to_teal_encoded_str());
+
+        let res = dao_global_state_lenient_from_app_state(
+            global.owner,
+            &ApplicationGlobalState(key_values),
+        );
+
+        assert!(matches!(
+            res,
+            Err(DaoGlobalStateError::MissingKey(key)) if key == GLOBAL_SHARE_PRICE
+        ));
+
+        Ok(())
+    }
+
+    /// (key, action, bytes, uint)
+    type TestDelta = (String, u64, Option<String>, Option<u64>);
+
+    struct TestTx {
+        created: Option<u64>,
+        called: Option<u64>,
+        sender: Address,
+        deltas: Vec<TestDelta>,
+        inner: Vec<TestTx>,
+    }
+
+    impl TestTx {
+        fn call(app_id: u64, deltas: Vec<TestDelta>, inner: Vec<TestTx>) -> TestTx {
+            TestTx {
+                created: None,
+                called: Some(app_id),
+                sender: Address([1; 32]),
+                deltas,
+                inner,
+            }
+        }
+    }
+
+    impl AppStateHistoryTx for TestTx {
+        fn created_app_id(&self) -> Option<u64> {
+            self.created
+        }
+
+        fn called_app_id(&self) -> Option<u64> {
+            self.called
+        }
+
+        fn sender(&self) -> Result<Address> {
+            Ok(self.sender)
+        }
+
+        fn global_state_deltas(&self) -> Vec<StateDelta<'_>> {
+            self.deltas
+                .iter()
+                .map(|(key, action, bytes, uint)| StateDelta {
+                    key,
+                    action: *action,
+                    bytes: bytes.as_deref(),
+                    uint: *uint,
+                })
+                .collect()
+        }
+
+        fn inner_txns(&self) -> &[Self] {
+            &self.inner
+        }
+    }
+
+    #[test]
+    fn test_replays_state_deltas() -> Result<()> {
+        let app_id = DaoAppId(123);
+        let global = global_state();
+        let share_price_key = GLOBAL_SHARE_PRICE.to_teal_encoded_str();
+        let deleted_key = AppStateKey("Deleted").to_teal_encoded_str();
+
+        // sets a key that's overwritten by the setup, and one that's deleted later
+        let creation = TestTx {
+            created: Some(app_id.0),
+            called: Some(0),
+            sender: global.owner,
+            deltas: vec![
+                (share_price_key.clone(), 2, None, Some(1)),
+                (deleted_key.clone(), 1, Some("AQI=".to_owned()), None),
+            ],
+            inner: vec![],
+        };
+        let setup_deltas = global
+            .to_teal_key_values()?
+            .into_iter()
+            .map(|kv| match kv.value.value_type {
+                1 => (kv.key, 1, Some(BASE64.encode(&kv.value.bytes)), None),
+                _ => (kv.key, 2, None, Some(kv.value.uint)),
+            })
+            .collect();
+        let setup = TestTx::call(app_id.0, setup_deltas, vec![]);
+        // a call to another app (whose deltas must be ignored), that calls the DAO with an inner transaction
+        let other_app_call = TestTx::call(
+            999,
+            vec![(share_price_key, 2, None, Some(5))],
+            vec![TestTx::call(
+                app_id.0,
+                vec![(deleted_key.clone(), 3, None, None)],
+                vec![],
+            )],
+        );
+
+        let mut state = BTreeMap::new();
+        let mut owner = None;
+
+        replay_app_txn(&mut state, &mut owner, &creation, app_id)?;
+        assert_eq!(Some(global.owner), owner);
+        assert_eq!(vec![1, 2], state[&deleted_key].bytes);
+
+        replay_app_txn(&mut state, &mut owner, &setup, app_id)?;
+        replay_app_txn(&mut state, &mut owner, &other_app_call, app_id)?;
+        assert!(!state.contains_key(&deleted_key));
+
+        let gs = ApplicationGlobalState(
+            state
+                .into_iter()
+                .map(|(key, value)| TealKeyValue { key, value })
+                .collect(),
+        );
+        assert_eq!(global, dao_global_state_from_app_state(global.owner, &gs)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_replay_errors_with_unknown_delta_action() -> Result<()> {
+        let tx = TestTx::call(123, vec![("a2V5".to_owned(), 4, None, Some(1))], vec![]);
+        let res = replay_app_txn(&mut BTreeMap::new(), &mut None, &tx, DaoAppId(123));
+        assert!(res.is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_state_errors_are_not_retryable() -> Result<()> {
+        let not_set_up = DaoGlobalStateError::NotSetUp {
+            actual_len: 0,
+            expected_len: 22,
+        };
+        assert!(!not_set_up.is_retryable());
+        assert!(!DaoGlobalStateError::MissingKey(GLOBAL_SHARE_PRICE).is_retryable());
+        assert!(!ApplicationLocalStateError::NotOptedIn.is_retryable());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ownership_share() -> Result<()> {
+        let mut global = global_state();
+        global.locked_shares = ShareAmount::new(3);
+
+        // rounded down
+        assert_eq!(
+            percentage("0.3333"),
+            investor_state(1).ownership_share(&global)?
+        );
+        assert_eq!(percentage("1"), investor_state(3).ownership_share(&global)?);
+        assert_eq!(percentage("0"), investor_state(0).ownership_share(&global)?);
+
+        // more shares than locked
+        assert!(investor_state(4).ownership_share(&global).is_err());
+
+        global.locked_shares = ShareAmount::new(0);
+        assert!(investor_state(0).ownership_share(&global).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_claimable_dividend_not_diluted_by_later_locks() -> Result<()> {
+        let mut global = global_state();
+        global.received = FundsAmount::new(1000);
+        global.locked_shares = ShareAmount::new(50);
+        let investor = investor_state(50);
+        let total = ShareAmount::new(100);
+
+        let before = investor.claimable_dividend(&global, total)?;
+        // another investor locks shares
+        global.locked_shares = ShareAmount::new(100);
+        assert_eq!(before, investor.claimable_dividend(&global, total)?);
+
+        Ok(())
+    }
+
+    /// Returns the queued results in order, counting the calls
+    struct CountingSource {
+        results: Vec<fn() -> Result<ApplicationGlobalState, DaoGlobalStateError>>,
+        calls: AtomicUsize,
+    }
+
+    #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+    #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+    impl GlobalStateSource for CountingSource {
+        async fn global_state(
+            &self,
+            _app_id: DaoAppId,
+        ) -> Result<(Address, ApplicationGlobalState), DaoGlobalStateError> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            let result = self.results[call.min(self.results.len() - 1)];
+            result().map(|gs| (global_state().owner, gs))
+        }
+    }
+
+    fn retry_policy() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(1),
+        }
+    }
+
+    #[test]
+    fn test_global_state_with_retry_doesnt_retry_not_set_up() -> Result<()> {
+        let source = CountingSource {
+            // empty: not set up
+            results: vec![|| Ok(ApplicationGlobalState(vec![]))],
+            calls: AtomicUsize::new(0),
+        };
+
+        let res = block_on(dao_global_state_with_retry_from_source(
+            &source,
+            DaoAppId(123),
+            retry_policy(),
+        ));
+        assert!(matches!(res, Err(DaoGlobalStateError::NotSetUp { .. })));
+        assert_eq!(1, source.calls.load(Ordering::SeqCst));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_global_state_with_retry_retries_server_errors() -> Result<()> {
+        let source = CountingSource {
+            results: vec![
+                || {
+                    Err(DaoGlobalStateError::Algod(ServiceError::Request(
+                        RequestError {
+                            url: None,
+                            details: RequestErrorDetails::Http {
+                                status: 503,
+                                message: "unavailable".to_owned(),
+                            },
+                        },
+                    )))
+                },
+                || Ok(ApplicationGlobalState(global_state().to_teal_key_values()?)),
+            ],
+            calls: AtomicUsize::new(0),
+        };
+
+        let res = block_on(dao_global_state_with_retry_from_source(
+            &source,
+            DaoAppId(123),
+            retry_policy(),
+        ))?;
+        assert_eq!(global_state(), res);
+        assert_eq!(2, source.calls.load(Ordering::SeqCst));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prospectus_verify_fetched() -> Result<()> {
+        let bytes = b"prospectus document".to_vec();
+        let prospectus = Prospectus::new(&bytes, "https://example.com".to_owned());
+        let timeout = Duration::from_millis(10);
+
+        let res = block_on(prospectus.verify_fetched(future::ready(Ok(bytes)), timeout));
+        assert_eq!(ProspectusVerifyResult::Ok, res);
+
+        let res =
+            block_on(prospectus.verify_fetched(future::ready(Ok(b"other".to_vec())), timeout));
+        assert_eq!(ProspectusVerifyResult::Mismatch, res);
+
+        let res = block_on(prospectus.verify_fetched(future::pending(), timeout));
+        assert!(matches!(res, ProspectusVerifyResult::Unreachable(_)));
+
+        Ok(())
+    }
+    /// Returns the pages in order, with a next token for every page (like the indexer when the pages are full),
+    /// and empty pages after the last one. Records the requested tokens.
+    struct PagedAccounts {
+        pages: Vec<Vec<Address>>,
+        requested: Mutex<Vec<Option<String>>>,
+    }
+
+    impl PagedAccounts {
+        fn new(pages: Vec<Vec<Address>>) -> PagedAccounts {
+            PagedAccounts {
+                pages,
+                requested: Mutex::new(vec![]),
+            }
+        }
+
+        fn requested(&self) -> Vec<Option<String>> {
+            self.requested.lock().unwrap().clone()
+        }
+    }
+
+    #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+    #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+    impl OptedInAccountsSource for PagedAccounts {
+        async fn opted_in_accounts(
+            &self,
+            _app_id: DaoAppId,
+            _limit: u64,
+            next: Option<String>,
+        ) -> Result<(Vec<Address>, Option<String>)> {
+            self.requested.lock().unwrap().push(next.clone());
+            let index: usize = next.map(|token| token.parse()).transpose()?.unwrap_or(0);
+            let page = self.pages.get(index).cloned().unwrap_or_default();
+            Ok((page, Some((index + 1).to_string())))
+        }
+    }
+
+    fn investors_source(app_id: DaoAppId, addresses: &[Address]) -> MockStateSource {
+        addresses
+            .iter()
+            .fold(MockStateSource::default(), |source, address| {
+                source.with_investor(app_id, *address, &investor_state(10))
+            })
+    }
+
+    #[test]
+    fn test_dao_investors_reads_all_pages() -> Result<()> {
+        let app_id = DaoAppId(123);
+        let (a, b, c) = (Address([1; 32]), Address([2; 32]), Address([3; 32]));
+        let accounts = PagedAccounts::new(vec![vec![a, b], vec![c]]);
+        let states = investors_source(app_id, &[a, b, c]);
+
+        let res = block_on(dao_investors_from_sources(&accounts, &states, app_id, None))?;
+
+        assert_eq!(
+            vec![
+                (a, Some(investor_state(10))),
+                (b, Some(investor_state(10))),
+                (c, Some(investor_state(10)))
+            ],
+            res.investors
+        );
+        assert!(res.failed.is_empty());
+        // stops at the empty page
+        assert_eq!(
+            vec![None, Some("1".to_owned()), Some("2".to_owned())],
+            accounts.requested()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dao_investors_limit() -> Result<()> {
+        let app_id = DaoAppId(123);
+        let (a, b, c) = (Address([1; 32]), Address([2; 32]), Address([3; 32]));
+        let states = investors_source(app_id, &[a, b, c]);
+
+        // within a page
+        let accounts = PagedAccounts::new(vec![vec![a, b], vec![c]]);
+        let res = block_on(dao_investors_from_sources(
+            &accounts,
+            &states,
+            app_id,
+            Some(1),
+        ))?;
+        assert_eq!(vec![(a, Some(investor_state(10)))], res.investors);
+
+        // at the end of a page: the next page isn't requested
+        let accounts = PagedAccounts::new(vec![vec![a, b], vec![c]]);
+        let res = block_on(dao_investors_from_sources(
+            &accounts,
+            &states,
+            app_id,
+            Some(2),
+        ))?;
+        assert_eq!(2, res.investors.len());
+        assert_eq!(vec![None], accounts.requested());
+
+        // 0
+        let accounts = PagedAccounts::new(vec![vec![a, b], vec![c]]);
+        let res = block_on(dao_investors_from_sources(
+            &accounts,
+            &states,
+            app_id,
+            Some(0),
+        ))?;
+        assert!(res.investors.is_empty());
+        assert!(accounts.requested().is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dao_investors_returns_uninitialized_and_failed_accounts() -> Result<()> {
+        let app_id = DaoAppId(123);
+        let invested = Address([1; 32]);
+        let opted_in = Address([2; 32]);
+        let malformed = Address([3; 32]);
+        let closed_out = Address([4; 32]);
+
+        let mut malformed_state = investor_local_state(vec![]);
+        malformed_state.schema.num_uint = LOCAL_SCHEMA_NUM_INTS + 1;
+        let states = investors_source(app_id, &[invested])
+            .with_local_state(app_id, opted_in, investor_local_state(vec![]))
+            .with_local_state(app_id, malformed, malformed_state);
+        let accounts = PagedAccounts::new(vec![vec![invested, opted_in, malformed, closed_out]]);
+
+        let res = block_on(dao_investors_from_sources(&accounts, &states, app_id, None))?;
+
+        assert_eq!(
+            vec![(invested, Some(investor_state(10))), (opted_in, None)],
+            res.investors
+        );
+        assert_eq!(1, res.failed.len());
+        assert_eq!(malformed, res.failed[0].0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lenient_parsing_reads_optional_keys_per_version() -> Result<()> {
+        let app_id = DaoAppId(123);
+        let mut global = global_state();
+        global.lockup_end = None;
+        global.voting_asset_id = None;
+        global.min_hold_period = None;
+        global.claim_deadline = None;
+
+        // version 1 doesn't have the optional keys
+        global.app_approval_version = Version(1);
+        let source = MockStateSource::default().with_dao(app_id, &global)?;
+        assert_eq!(
+            global,
+            block_on(dao_global_state_lenient_from_source(&source, app_id))?
+        );
+
+        // version 2 has them, None stored as 0
+        global.app_approval_version = Version(2);
+        let source = MockStateSource::default().with_dao(app_id, &global)?;
+        assert_eq!(
+            global,
+            block_on(dao_global_state_lenient_from_source(&source, app_id))?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lenient_parsing_fails_without_optional_key_of_version() -> Result<()> {
+        let mut global = global_state();
+        global.app_approval_version = Version(2);
+        let mut key_values = global.to_teal_key_values()?;
+        key_values.retain(|kv| kv.key != GLOBAL_VOTING_ASSET_ID.to_teal_encoded_str());
+
+        let res = dao_global_state_lenient_from_app_state(
+            global.owner,
+            &ApplicationGlobalState(key_values),
+        );
+
+        assert!(matches!(
+            res,
+            Err(DaoGlobalStateError::MissingKey(key)) if key == GLOBAL_VOTING_ASSET_ID
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dao_global_states_keeps_order_and_fails_only_unknown_dao() -> Result<()> {
+        let first = mid_raise().global;
+        let second = funded_and_operating().global;
+        let third = fully_claimed().global;
+        let source = MockStateSource::default()
+            .with_dao(DaoAppId(1), &first)?
+            .with_dao(DaoAppId(2), &second)?
+            .with_dao(DaoAppId(3), &third)?;
+        let app_ids = [DaoAppId(3), DaoAppId(99), DaoAppId(1), DaoAppId(2)];
+
+        let results = block_on(dao_global_states(&source, &app_ids, 3))?;
+
+        assert_eq!(
+            app_ids.to_vec(),
+            results.iter().map(|(id, _)| *id).collect::<Vec<_>>()
+        );
+        assert_eq!(Some(&third), results[0].1.as_ref().ok());
+        assert!(matches!(&results[1].1, Err(DaoGlobalStateError::Msg(_))));
+        assert_eq!(Some(&first), results[2].1.as_ref().ok());
+        assert_eq!(Some(&second), results[3].1.as_ref().ok());
+
+        Ok(())
+    }
+}
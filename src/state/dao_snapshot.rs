@@ -0,0 +1,247 @@
+use super::dao_app_state::{CentralAppGlobalState, CentralAppInvestorState};
+use crate::models::{dao_app_id::DaoAppId, funds::FundsAmount, share_amount::ShareAmount};
+use algonaut::core::Address;
+
+/// The state of a DAO and its investors at a point in time
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DaoSnapshot {
+    pub app_id: DaoAppId,
+    pub global: CentralAppGlobalState,
+    pub investors: Vec<(Address, CentralAppInvestorState)>,
+}
+
+impl DaoSnapshot {
+    pub fn investor(&self, address: &Address) -> Option<&CentralAppInvestorState> {
+        self.investors
+            .iter()
+            .find(|(a, _)| a == address)
+            .map(|(_, state)| state)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StateEvent {
+    /// A setting of the DAO (e.g. name, share price, prospectus) changed
+    ConfigChanged {
+        field: &'static str,
+    },
+    ReceivedIncreased {
+        old: FundsAmount,
+        new: FundsAmount,
+    },
+    NewInvestor {
+        investor: Address,
+        shares: ShareAmount,
+    },
+    SharesChanged {
+        investor: Address,
+        old: ShareAmount,
+        new: ShareAmount,
+    },
+    DividendClaimed {
+        investor: Address,
+        amount: FundsAmount,
+    },
+    /// The investor isn't in the new snapshot anymore (e.g. closed out)
+    InvestorLeft {
+        investor: Address,
+    },
+}
+
+/// Events that explain the difference between 2 snapshots of the same DAO
+///
+/// The snapshots don't tell in which order things happened, so the order is just approximately chronological:
+/// config changes, income, and then investor events, in the order of the new snapshot's investors, followed by the investors that left.
+pub fn emit_events(old_snapshot: &DaoSnapshot, new_snapshot: &DaoSnapshot) -> Vec<StateEvent> {
+    let mut events = config_events(&old_snapshot.global, &new_snapshot.global);
+
+    if new_snapshot.global.received.val() > old_snapshot.global.received.val() {
+        events.push(StateEvent::ReceivedIncreased {
+            old: old_snapshot.global.received,
+            new: new_snapshot.global.received,
+        });
+    }
+
+    for (investor, new) in &new_snapshot.investors {
+        match old_snapshot.investor(investor) {
+            Some(old) => events.extend(investor_events(investor, old, new)),
+            None => {
+                events.push(StateEvent::NewInvestor {
+                    investor: *investor,
+                    shares: new.shares,
+                });
+                if new.already_claimed().val() > 0 {
+                    events.push(StateEvent::DividendClaimed {
+                        investor: *investor,
+                        amount: new.already_claimed(),
+                    });
+                }
+            }
+        }
+    }
+
+    for (investor, _) in &old_snapshot.investors {
+        if new_snapshot.investor(investor).is_none() {
+            events.push(StateEvent::InvestorLeft {
+                investor: *investor,
+            });
+        }
+    }
+
+    events
+}
+
+/// Fields of the global state that aren't settings: they change with the DAO's activity (income, investments, withdrawals),
+/// or are set once at the DAO's setup.
+/// The other fields (including newly added ones) are reported as [StateEvent::ConfigChanged].
+const NON_CONFIG_FIELDS: &[&str] = &[
+    "received",
+    "available",
+    "funds_asset_id",
+    "shares_asset_id",
+    "owner",
+    "locked_shares",
+    "raised",
+    "setup_date",
+];
+
+fn config_events(old: &CentralAppGlobalState, new: &CentralAppGlobalState) -> Vec<StateEvent> {
+    old.diff(new)
+        .into_iter()
+        .filter(|change| !NON_CONFIG_FIELDS.contains(&change.field))
+        .map(|change| StateEvent::ConfigChanged {
+            field: change.field,
+        })
+        .collect()
+}
+
+fn investor_events(
+    investor: &Address,
+    old: &CentralAppInvestorState,
+    new: &CentralAppInvestorState,
+) -> Vec<StateEvent> {
+    let mut events = vec![];
+
+    if old.shares != new.shares {
+        events.push(StateEvent::SharesChanged {
+            investor: *investor,
+            old: old.shares,
+            new: new.shares,
+        });
+    }
+
+    // claimed_init is adjusted when locking shares, so only the difference to it has been claimed
+    let (old_claimed, new_claimed) = (old.already_claimed().val(), new.already_claimed().val());
+    if new_claimed > old_claimed {
+        events.push(StateEvent::DividendClaimed {
+            investor: *investor,
+            amount: FundsAmount::new(new_claimed - old_claimed),
+        });
+    }
+
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{config_events, emit_events, DaoSnapshot, StateEvent};
+    use crate::{
+        fixtures::{global_state, investor_state},
+        models::{dao_app_id::DaoAppId, funds::FundsAmount, share_amount::ShareAmount},
+    };
+    use algonaut::core::Address;
+    use anyhow::Result;
+
+    #[test]
+    fn test_emits_events_for_multiple_changes() -> Result<()> {
+        let staying = Address([1; 32]);
+        let leaving = Address([2; 32]);
+        let new = Address([3; 32]);
+
+        let old_snapshot = DaoSnapshot {
+            app_id: DaoAppId(123),
+            global: global_state(),
+            investors: vec![(staying, investor_state(10)), (leaving, investor_state(5))],
+        };
+
+        let mut new_global = global_state();
+        new_global.received = FundsAmount::new(1000);
+        new_global.project_name = "renamed dao".to_owned();
+
+        let mut staying_state = investor_state(20);
+        staying_state.claimed_init = FundsAmount::new(100);
+        staying_state.claimed = FundsAmount::new(150);
+
+        let new_snapshot = DaoSnapshot {
+            app_id: DaoAppId(123),
+            global: new_global,
+            investors: vec![(staying, staying_state), (new, investor_state(30))],
+        };
+
+        let events = emit_events(&old_snapshot, &new_snapshot);
+
+        assert_eq!(
+            vec![
+                StateEvent::ConfigChanged {
+                    field: "project_name"
+                },
+                StateEvent::ReceivedIncreased {
+                    old: FundsAmount::new(0),
+                    new: FundsAmount::new(1000)
+                },
+                StateEvent::SharesChanged {
+                    investor: staying,
+                    old: ShareAmount::new(10),
+                    new: ShareAmount::new(20)
+                },
+                StateEvent::DividendClaimed {
+                    investor: staying,
+                    amount: FundsAmount::new(50)
+                },
+                StateEvent::NewInvestor {
+                    investor: new,
+                    shares: ShareAmount::new(30)
+                },
+                StateEvent::InvestorLeft { investor: leaving },
+            ],
+            events
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_events_for_identical_snapshots() -> Result<()> {
+        let snapshot = DaoSnapshot {
+            app_id: DaoAppId(123),
+            global: global_state(),
+            investors: vec![(Address([1; 32]), investor_state(10))],
+        };
+
+        assert!(emit_events(&snapshot, &snapshot).is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_events_for_settings_only() -> Result<()> {
+        let old = global_state();
+        let mut new = global_state();
+        new.share_price = FundsAmount::new(old.share_price.val() + 1);
+        new.team_url = Some("https://example.com/team".to_owned());
+        new.raised = FundsAmount::new(old.raised.val() + 1);
+        new.locked_shares = ShareAmount::new(old.locked_shares.val() + 1);
+
+        assert_eq!(
+            vec![
+                StateEvent::ConfigChanged {
+                    field: "share_price"
+                },
+                StateEvent::ConfigChanged { field: "team_url" },
+            ],
+            config_events(&old, &new)
+        );
+
+        Ok(())
+    }
+}
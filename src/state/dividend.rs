@@ -0,0 +1,224 @@
+use super::dao_app_state::CentralAppGlobalState;
+use crate::models::{
+    funds::FundsAmount, share_amount::ShareAmount, shares_percentage::SharesPercentage,
+};
+use anyhow::{anyhow, Result};
+use std::convert::TryInto;
+
+/// Dividend that `shares` are entitled to, for everything the DAO has received:
+/// `received * investors share * shares / total shares`, rounded down.
+///
+/// `total_shares` is the shares asset's supply, which doesn't change: so the entitlement per share only increases (with `received`),
+/// and locking or transferring shares doesn't affect what the other holders are entitled to.
+/// Errors if `total_shares` is 0 or `shares` exceed it.
+pub fn entitled_dividend(
+    global: &CentralAppGlobalState,
+    shares: ShareAmount,
+    total_shares: ShareAmount,
+) -> Result<FundsAmount> {
+    if total_shares.val() == 0 {
+        return Err(anyhow!("Total shares must not be 0"));
+    }
+    if shares > total_shares {
+        return Err(anyhow!(
+            "Shares: {shares} must be <= total shares: {total_shares}"
+        ));
+    }
+    if shares.val() == 0 {
+        return Ok(FundsAmount::new(0));
+    }
+
+    let overflow = || {
+        anyhow!(
+            "Overflow calculating dividend: received: {}, shares: {shares}, total: {total_shares}",
+            global.received
+        )
+    };
+
+    // integer math (using the percentage's integer representation) to round down exactly
+    let investors_share = global.investors_share.to_u64()? as u128;
+    let precision = SharesPercentage::integer_precision() as u128;
+
+    let numerator = (global.received.val() as u128)
+        .checked_mul(investors_share)
+        .and_then(|n| n.checked_mul(shares.val() as u128))
+        .ok_or_else(overflow)?;
+    let denominator = precision
+        .checked_mul(total_shares.val() as u128)
+        .ok_or_else(overflow)?;
+
+    Ok(FundsAmount::new(
+        (numerator / denominator)
+            .try_into()
+            .map_err(|_| overflow())?,
+    ))
+}
+
+/// Value to which `claimed_init` (and `claimed`) is initialized when locking `shares`:
+/// the dividend they're entitled to for the income received before locking, which they can't claim.
+///
+/// Since the entitlement is relative to the total supply (see [entitled_dividend]), locking doesn't change the other holders' entitlement,
+/// and after locking the investor can claim exactly the dividend for the income received afterwards.
+pub fn lock_claimed_init(
+    global: &CentralAppGlobalState,
+    shares: ShareAmount,
+    total_shares: ShareAmount,
+) -> Result<FundsAmount> {
+    entitled_dividend(global, shares, total_shares)
+}
+
+/// Increment of the receiver's `claimed_init` (and `claimed`) when `transferred_shares` are transferred to them.
+///
+/// Like when locking, this sets the receiver's claimed amount for the transferred shares to what they're entitled to for the income received until now.
+/// This way the receiver can only claim the dividend for income received after the transfer,
+/// and the dividend accrued before the transfer can't be claimed twice (by the sender, and then by the receiver):
+/// the entitlement per share never decreases, so the claimed amount the receiver starts with is never more than what the shares go on to be entitled to.
+/// The sender's entitlement decreases with their shares, so the sender should claim before transferring.
+///
+/// This is the same calculation as [lock_claimed_init]: for the receiver, receiving locked shares is equivalent to locking them.
+pub fn compute_transfer_claimed_init(
+    global: &CentralAppGlobalState,
+    transferred_shares: ShareAmount,
+    total_shares: ShareAmount,
+) -> Result<FundsAmount> {
+    entitled_dividend(global, transferred_shares, total_shares)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compute_transfer_claimed_init, entitled_dividend, lock_claimed_init};
+    use crate::{
+        fixtures::{global_state, investor_state, percentage},
+        models::{funds::FundsAmount, share_amount::ShareAmount},
+        state::dao_app_state::CentralAppGlobalState,
+    };
+    use anyhow::Result;
+
+    fn global(received: u64, locked_shares: u64) -> CentralAppGlobalState {
+        let mut global = global_state();
+        global.received = FundsAmount::new(received);
+        global.investors_share = percentage("0.4");
+        global.locked_shares = ShareAmount::new(locked_shares);
+        global
+    }
+
+    #[test]
+    fn test_entitled_dividend_rounds_down() -> Result<()> {
+        // 1000 * 0.4 * 1 / 3 = 133.33..
+        let res = entitled_dividend(&global(1000, 3), ShareAmount::new(1), ShareAmount::new(3))?;
+        assert_eq!(FundsAmount::new(133), res);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_entitled_dividend_is_relative_to_total_shares() -> Result<()> {
+        // 1000 * 0.4 * 10 / 100, regardless of how many shares are locked
+        for locked in [10, 50, 100] {
+            let res = entitled_dividend(
+                &global(1000, locked),
+                ShareAmount::new(10),
+                ShareAmount::new(100),
+            )?;
+            assert_eq!(FundsAmount::new(40), res);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_entitled_dividend_errors_with_invalid_total_shares() -> Result<()> {
+        let global = global(1000, 0);
+        assert!(entitled_dividend(&global, ShareAmount::new(0), ShareAmount::new(0)).is_err());
+        assert!(entitled_dividend(&global, ShareAmount::new(11), ShareAmount::new(10)).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lock_claimed_init_is_0_without_income() -> Result<()> {
+        let res = lock_claimed_init(&global(0, 100), ShareAmount::new(50), ShareAmount::new(200))?;
+        assert_eq!(FundsAmount::new(0), res);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lock_doesnt_change_other_holders_entitlement() -> Result<()> {
+        let total = ShareAmount::new(100);
+        let holder_shares = ShareAmount::new(30);
+        let before_lock = global(1000, 30);
+        let after_lock = global(1000, 80);
+
+        assert_eq!(
+            entitled_dividend(&before_lock, holder_shares, total)?,
+            entitled_dividend(&after_lock, holder_shares, total)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_transfer_claimed_init() -> Result<()> {
+        // 1000 * 0.4 * 25 / 200
+        let res = compute_transfer_claimed_init(
+            &global(1000, 100),
+            ShareAmount::new(25),
+            ShareAmount::new(200),
+        )?;
+        assert_eq!(FundsAmount::new(50), res);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lock_followed_by_transfer() -> Result<()> {
+        let total = ShareAmount::new(100);
+
+        // A locks 25 shares after the DAO received 1000: 1000 * 0.4 * 25 / 100
+        let init_a = lock_claimed_init(&global(1000, 75), ShareAmount::new(25), total)?;
+        assert_eq!(FundsAmount::new(100), init_a);
+        let mut investor_a = investor_state(25);
+        investor_a.claimed_init = init_a;
+        investor_a.claimed = init_a;
+
+        // the DAO receives 1000 more: A can claim only the dividend for it: 1000 * 0.4 * 25 / 100
+        let global_2000 = global(2000, 100);
+        assert_eq!(
+            FundsAmount::new(100),
+            investor_a.claimable_dividend(&global_2000, total)?
+        );
+
+        // B receives 10 shares: 2000 * 0.4 * 10 / 100
+        let init_b = compute_transfer_claimed_init(&global_2000, ShareAmount::new(10), total)?;
+        assert_eq!(FundsAmount::new(80), init_b);
+        let mut investor_b = investor_state(10);
+        investor_b.claimed_init = init_b;
+        investor_b.claimed = init_b;
+        // nothing accrued before the transfer is claimable by B
+        assert_eq!(
+            FundsAmount::new(0),
+            investor_b.claimable_dividend(&global_2000, total)?
+        );
+
+        // the DAO receives 1000 more: B can claim the dividend for it: 1000 * 0.4 * 10 / 100
+        assert_eq!(
+            FundsAmount::new(40),
+            investor_b.claimable_dividend(&global(3000, 100), total)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_transfer_claimed_init_errors_if_more_than_total() -> Result<()> {
+        let res = compute_transfer_claimed_init(
+            &global(1000, 10),
+            ShareAmount::new(11),
+            ShareAmount::new(10),
+        );
+        assert!(res.is_err());
+
+        Ok(())
+    }
+}
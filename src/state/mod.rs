@@ -1,2 +1,5 @@
 pub mod app_state;
 pub mod dao_app_state;
+pub mod dao_snapshot;
+pub mod dividend;
+pub mod state_source;
@@ -0,0 +1,104 @@
+use super::{
+    app_state::{local_state, ApplicationGlobalState, ApplicationLocalStateError},
+    dao_app_state::DaoGlobalStateError,
+};
+use crate::models::dao_app_id::DaoAppId;
+use algonaut::{
+    algod::v2::Algod,
+    core::Address,
+    indexer::v2::Indexer,
+    model::{algod::v2::ApplicationLocalState, indexer::v2::QueryAccount},
+};
+use anyhow::Result;
+use async_trait::async_trait;
+
+/// Where the DAO's global state is read from. Algod in production,
+/// but it can be anything that returns the state like algod does (e.g. a cache or a mock).
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait GlobalStateSource {
+    /// The app's creator (the DAO's owner, which isn't part of the state) and global state
+    async fn global_state(
+        &self,
+        app_id: DaoAppId,
+    ) -> Result<(Address, ApplicationGlobalState), DaoGlobalStateError>;
+}
+
+/// Where the investors' local state is read from (see [GlobalStateSource])
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait LocalStateSource {
+    async fn local_state(
+        &self,
+        address: &Address,
+        app_id: DaoAppId,
+    ) -> Result<ApplicationLocalState, ApplicationLocalStateError<'static>>;
+}
+
+/// Lists the accounts opted in to an app, a page at a time. The indexer in production (see [GlobalStateSource])
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait OptedInAccountsSource {
+    /// Up to `limit` accounts, starting at the page `next` (None: the first page),
+    /// and the token of the next page (None: there are no more accounts)
+    async fn opted_in_accounts(
+        &self,
+        app_id: DaoAppId,
+        limit: u64,
+        next: Option<String>,
+    ) -> Result<(Vec<Address>, Option<String>)>;
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl GlobalStateSource for Algod {
+    async fn global_state(
+        &self,
+        app_id: DaoAppId,
+    ) -> Result<(Address, ApplicationGlobalState), DaoGlobalStateError> {
+        let app = self.application_information(app_id.0).await?;
+        Ok((
+            app.params.creator,
+            ApplicationGlobalState(app.params.global_state),
+        ))
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl LocalStateSource for Algod {
+    async fn local_state(
+        &self,
+        address: &Address,
+        app_id: DaoAppId,
+    ) -> Result<ApplicationLocalState, ApplicationLocalStateError<'static>> {
+        local_state(self, address, app_id.0).await
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl OptedInAccountsSource for Indexer {
+    async fn opted_in_accounts(
+        &self,
+        app_id: DaoAppId,
+        limit: u64,
+        next: Option<String>,
+    ) -> Result<(Vec<Address>, Option<String>)> {
+        let page = self
+            .accounts(&QueryAccount {
+                application_id: Some(app_id.0),
+                limit: Some(limit),
+                next,
+                ..QueryAccount::default()
+            })
+            .await?;
+        Ok((
+            page.accounts
+                .iter()
+                .map(|account| account.address)
+                .collect(),
+            page.next_token,
+        ))
+    }
+}
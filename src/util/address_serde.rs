@@ -0,0 +1,12 @@
+use algonaut::core::Address;
+use serde::{de, Deserialize, Deserializer, Serializer};
+
+/// Serializes the address as its base32 string. Use with `#[serde(with = "crate::util::address_serde")]`.
+pub fn serialize<S: Serializer>(address: &Address, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&address.to_string())
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Address, D::Error> {
+    let str = String::deserialize(deserializer)?;
+    str.parse().map_err(de::Error::custom)
+}
@@ -0,0 +1,39 @@
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IpfsConfig {
+    /// e.g. https://ipfs.io
+    pub gateway_url: String,
+}
+
+impl IpfsConfig {
+    /// Maps ipfs:// urls to the gateway, other urls are returned unchanged
+    pub fn resolve(&self, url: &str) -> String {
+        match url.strip_prefix("ipfs://") {
+            Some(path) => format!("{}/ipfs/{path}", self.gateway_url.trim_end_matches('/')),
+            None => url.to_owned(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IpfsConfig;
+    use anyhow::Result;
+
+    #[test]
+    fn test_resolves_ipfs_urls_with_gateway() -> Result<()> {
+        let config = IpfsConfig {
+            gateway_url: "https://ipfs.io/".to_owned(),
+        };
+
+        assert_eq!(
+            "https://ipfs.io/ipfs/bafybeigdyrzt",
+            config.resolve("ipfs://bafybeigdyrzt")
+        );
+        assert_eq!(
+            "https://example.com/prospectus.pdf",
+            config.resolve("https://example.com/prospectus.pdf")
+        );
+
+        Ok(())
+    }
+}
@@ -2,3 +2,8 @@ pub mod decimal_util;
 pub mod files;
 pub mod algo_helpers;
 pub mod network_util;
+pub mod ipfs;
+pub mod address_serde;
+pub mod retry;
+pub mod shares_percentage_serde;
+pub mod optional_duration_secs_serde;
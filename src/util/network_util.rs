@@ -1,6 +1,8 @@
 use algonaut::{algod::v2::Algod, error::ServiceError, model::algod::v2::PendingTransaction};
+use anyhow::{anyhow, Result};
+use futures::future::{select, Either};
 use instant::Instant;
-use std::time::Duration;
+use std::{convert::TryInto, future::Future, time::Duration};
 
 use crate::models::tx_id::TxId;
 
@@ -27,6 +29,15 @@ pub async fn wait_for_pending_transaction(
     }
 }
 
+/// The future's output, or an error if it doesn't complete within `timeout`
+pub async fn with_timeout<F: Future>(future: F, timeout: Duration) -> Result<F::Output> {
+    let timer = sleep(timeout.as_millis().try_into().unwrap_or(u32::MAX));
+    match select(Box::pin(future), Box::pin(timer)).await {
+        Either::Left((output, _)) => Ok(output),
+        Either::Right(_) => Err(anyhow!("Timed out after {timeout:?}")),
+    }
+}
+
 #[cfg(target_arch = "wasm32")]
 pub async fn sleep(ms: u32) {
     gloo_timers::future::TimeoutFuture::new(ms).await;
@@ -36,3 +47,25 @@ pub async fn sleep(ms: u32) {
 pub async fn sleep(ms: u32) {
     futures_timer::Delay::new(std::time::Duration::from_millis(ms as u64)).await;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::with_timeout;
+    use anyhow::Result;
+    use futures::{executor::block_on, future};
+    use std::time::Duration;
+
+    #[test]
+    fn test_with_timeout() -> Result<()> {
+        let res = block_on(with_timeout(future::ready(1), Duration::from_secs(10)))?;
+        assert_eq!(1, res);
+
+        let res = block_on(with_timeout(
+            future::pending::<()>(),
+            Duration::from_millis(1),
+        ));
+        assert!(res.is_err());
+
+        Ok(())
+    }
+}
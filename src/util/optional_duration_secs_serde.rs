@@ -0,0 +1,18 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::time::Duration;
+
+/// Serializes the optional duration as its whole seconds, like it's stored in the app state.
+/// Use with `#[serde(with = "crate::util::optional_duration_secs_serde")]`.
+pub fn serialize<S: Serializer>(
+    duration: &Option<Duration>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    duration.map(|d| d.as_secs()).serialize(serializer)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Duration>, D::Error> {
+    let secs = Option::<u64>::deserialize(deserializer)?;
+    Ok(secs.map(Duration::from_secs))
+}
@@ -0,0 +1,216 @@
+use super::network_util::sleep;
+use algonaut::error::{RequestError, RequestErrorDetails, ServiceError};
+use chrono::Utc;
+use std::{convert::TryInto, future::Future, time::Duration};
+
+/// Whether an error may go away by retrying the operation that caused it.
+///
+/// Only transient network errors are retryable (the request may succeed a bit later):
+/// errors caused by the content of the state (e.g. not set up, malformed, not opted in) won't fix themselves, so they fail immediately.
+pub trait Retryable {
+    fn is_retryable(&self) -> bool;
+}
+
+impl Retryable for ServiceError {
+    fn is_retryable(&self) -> bool {
+        match self {
+            ServiceError::Request(RequestError { details, .. }) => match details {
+                // rate limited or server error
+                RequestErrorDetails::Http { status, .. } => {
+                    *status == 429 || (500..600).contains(status)
+                }
+                RequestErrorDetails::Timeout => true,
+                // transport (e.g. connection refused or reset)
+                RequestErrorDetails::Client { .. } => true,
+            },
+            _ => false,
+        }
+    }
+}
+
+/// Exponential backoff with jitter: retry n waits `base_delay * 2^(n - 1)`, capped at `max_delay`,
+/// of which the second half is random, so clients that failed at the same time don't retry at the same time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Including the first attempt (0 is treated as 1)
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 4,
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(2),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Only one attempt
+    pub fn no_retry() -> Self {
+        RetryPolicy {
+            max_attempts: 1,
+            ..RetryPolicy::default()
+        }
+    }
+
+    /// `retry`: 1 for the first retry. `jitter`: in [0, 1]
+    fn delay(&self, retry: u32, jitter: f64) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        let delay = self.base_delay.saturating_mul(factor).min(self.max_delay);
+        let half = delay / 2;
+        half + half.mul_f64(jitter.clamp(0., 1.))
+    }
+}
+
+/// Runs `f` until it succeeds, it returns an error that's not retryable, or the attempts are exhausted.
+/// Returns the last error.
+pub async fn with_retry<T, E, F, Fut>(policy: RetryPolicy, mut f: F) -> Result<T, E>
+where
+    E: Retryable + std::fmt::Debug,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let max_attempts = policy.max_attempts.max(1);
+    let mut attempt = 1;
+    loop {
+        match f().await {
+            Ok(res) => return Ok(res),
+            Err(e) if attempt < max_attempts && e.is_retryable() => {
+                let delay = policy.delay(attempt, jitter());
+                log::debug!(
+                    "Attempt {attempt}/{max_attempts} failed: {e:?}, retrying in {delay:?}"
+                );
+                sleep(delay.as_millis().try_into().unwrap_or(u32::MAX)).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Pseudo random number in [0, 1]: good enough to spread retries, without a random number generator dependency
+fn jitter() -> f64 {
+    Utc::now().timestamp_subsec_nanos() as f64 / 1_000_000_000.
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{with_retry, RetryPolicy, Retryable};
+    use algonaut::error::{RequestError, RequestErrorDetails, ServiceError};
+    use anyhow::Result;
+    use futures::executor::block_on;
+    use std::{cell::Cell, time::Duration};
+
+    #[derive(Debug, PartialEq, Eq)]
+    enum TestError {
+        Transient,
+        Terminal,
+    }
+
+    impl Retryable for TestError {
+        fn is_retryable(&self) -> bool {
+            matches!(self, TestError::Transient)
+        }
+    }
+
+    fn policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(1),
+        }
+    }
+
+    #[test]
+    fn test_retries_transient_errors_until_success() -> Result<()> {
+        let attempts = Cell::new(0);
+        let res = block_on(with_retry(policy(3), || async {
+            attempts.set(attempts.get() + 1);
+            if attempts.get() < 3 {
+                Err(TestError::Transient)
+            } else {
+                Ok(attempts.get())
+            }
+        }));
+        assert_eq!(Ok(3), res);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stops_after_max_attempts() -> Result<()> {
+        let attempts = Cell::new(0);
+        let res: Result<(), _> = block_on(with_retry(policy(3), || async {
+            attempts.set(attempts.get() + 1);
+            Err(TestError::Transient)
+        }));
+        assert_eq!(Err(TestError::Transient), res);
+        assert_eq!(3, attempts.get());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_terminal_error_fails_fast() -> Result<()> {
+        let attempts = Cell::new(0);
+        let res: Result<(), _> = block_on(with_retry(policy(3), || async {
+            attempts.set(attempts.get() + 1);
+            Err(TestError::Terminal)
+        }));
+        assert_eq!(Err(TestError::Terminal), res);
+        assert_eq!(1, attempts.get());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_backoff_delay() -> Result<()> {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(1000),
+        };
+        // without jitter: half of the exponential delay, with full jitter: all of it
+        assert_eq!(Duration::from_millis(50), policy.delay(1, 0.));
+        assert_eq!(Duration::from_millis(100), policy.delay(1, 1.));
+        assert_eq!(Duration::from_millis(200), policy.delay(2, 1.));
+        assert_eq!(Duration::from_millis(400), policy.delay(3, 1.));
+        // capped
+        assert_eq!(Duration::from_millis(1000), policy.delay(5, 1.));
+        assert_eq!(Duration::from_millis(1000), policy.delay(100, 1.));
+
+        Ok(())
+    }
+
+    fn request_error(details: RequestErrorDetails) -> ServiceError {
+        ServiceError::Request(RequestError { url: None, details })
+    }
+
+    fn http_error(status: u16) -> ServiceError {
+        request_error(RequestErrorDetails::Http {
+            status,
+            message: "error".to_owned(),
+        })
+    }
+
+    #[test]
+    fn test_service_error_classification() -> Result<()> {
+        // rate limited
+        assert!(http_error(429).is_retryable());
+        // server errors
+        assert!(http_error(500).is_retryable());
+        assert!(http_error(503).is_retryable());
+        assert!(request_error(RequestErrorDetails::Timeout).is_retryable());
+
+        // client errors won't change by retrying
+        assert!(!http_error(400).is_retryable());
+        assert!(!http_error(404).is_retryable());
+        assert!(!ServiceError::Msg("error".to_owned()).is_retryable());
+
+        Ok(())
+    }
+}
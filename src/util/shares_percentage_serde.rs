@@ -0,0 +1,20 @@
+use crate::models::shares_percentage::SharesPercentage;
+use serde::{de, ser, Deserialize, Deserializer, Serializer};
+use std::convert::TryInto;
+
+/// Serializes the percentage as its integer representation, like it's stored in the app state (see [SharesPercentage::to_u64]).
+/// Use with `#[serde(with = "crate::util::shares_percentage_serde")]`.
+pub fn serialize<S: Serializer>(
+    percentage: &SharesPercentage,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let integer = percentage.to_u64().map_err(ser::Error::custom)?;
+    serializer.serialize_u64(integer)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<SharesPercentage, D::Error> {
+    let integer = u64::deserialize(deserializer)?;
+    integer.try_into().map_err(de::Error::custom)
+}